@@ -1,13 +1,22 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use crate::t;
 
 use colored::Colorize;
 use crate::config::Config;
-use crate::core::{self, aliases::Aliases, versions};
+use crate::core::{self, lock::NvmLock, versions};
+use crate::core::aliases::{AliasEntry, Aliases};
 
 
 /// Create or update an alias
-pub async fn create_alias(name: String, version: String, config: &Config) -> Result<()> {
+/// Con `track` guarda el spec (`lts/*`, `^20`) y se vuelve a resolver en cada uso;
+/// sin él, el alias queda fijo en la versión que resuelve ahora
+pub async fn create_alias(
+    name: String,
+    version: String,
+    track: bool,
+    description: Option<String>,
+    config: &Config,
+) -> Result<()> {
     // Validar nombre de alias
     if !Aliases::is_valid_alias_name(&name) {
         eprintln!("{}", t!("invalid_alias_name").replace("{name}", &name));
@@ -18,8 +27,22 @@ pub async fn create_alias(name: String, version: String, config: &Config) -> Res
     // Obtener versiones disponibles para resolver aliases
     let available_versions = core::get_cached_versions(config).await?;
 
+    // Un alias que sigue a otro no puede terminar apuntándose a sí mismo
+    if track && Aliases::load()?.chain(&version).contains(&name) {
+        bail!("Alias '{}' cannot track itself ({})", name, version);
+    }
+
     // Resolver la versión (puede ser otro alias o versión directa)
-    let resolved_version = versions::resolve_version(&version, &available_versions)?;
+    let resolved_version = if track {
+        // Lo mismo que elegirá al usarlo: la más nueva instalada que cumple el spec, si hay una
+        let installed = core::get_installed_versions(config).unwrap_or_default();
+        match versions::find_installed_matching(&version, &installed, &available_versions) {
+            Some(installed_version) => installed_version,
+            None => super::install::resolve_prefer_installed(&version, &available_versions, config)?,
+        }
+    } else {
+        versions::resolve_version(&version, &available_versions)?
+    };
 
     // Verificar que la versión exista (instalada o disponible)
     let version_dir = config.versions_dir().join(&resolved_version);
@@ -35,19 +58,28 @@ pub async fn create_alias(name: String, version: String, config: &Config) -> Res
     }
 
     // Cargar aliases existentes
+    let _lock = NvmLock::acquire(config)?;
     let mut aliases = Aliases::load()?;
 
     // Verificar si es actualización o creación
     let is_update = aliases.contains(&name);
 
     // Establecer el alias
-    aliases.set(name.clone(), resolved_version.clone());
+    let mut entry = AliasEntry::new(if track { version.trim().to_string() } else { resolved_version.clone() });
+    entry.tracking = track;
+    entry.description = description;
+    aliases.insert(name.clone(), entry);
 
     // Guardar aliases
     aliases.save()?;
 
     // Mostrar mensaje apropiado
-    if is_update {
+    if track {
+        println!("{}", t!("alias_tracking")
+            .replace("{name}", &name)
+            .replace("{spec}", version.trim())
+            .replace("{version}", &resolved_version));
+    } else if is_update {
         println!("{}", t!("alias_updated")
             .replace("{name}", &name)
             .replace("{version}", &resolved_version));
@@ -61,8 +93,9 @@ pub async fn create_alias(name: String, version: String, config: &Config) -> Res
 }
 
 /// Remove an alias
-pub fn remove_alias(name: String) -> Result<()> {
+pub fn remove_alias(name: String, config: &Config) -> Result<()> {
     // Cargar aliases existentes
+    let _lock = NvmLock::acquire(config)?;
     let mut aliases = Aliases::load()?;
 
     // Intentar eliminar el alias
@@ -80,7 +113,8 @@ pub fn remove_alias(name: String) -> Result<()> {
 }
 
 /// List all defined aliases
-pub fn list_aliases() -> Result<()> {
+/// Los que siguen un spec muestran además la versión a la que resuelven hoy (sin usar la red)
+pub fn list_aliases(config: &Config) -> Result<()> {
     // Cargar aliases
     let aliases = Aliases::load()?;
 
@@ -93,10 +127,27 @@ pub fn list_aliases() -> Result<()> {
     // Mostrar título
     println!("\n{}", t!("aliases_list_title"));
 
+    let installed = core::get_installed_versions(config).unwrap_or_default();
+    let available = core::read_cached_versions(config);
+
     // Obtener y mostrar aliases ordenados
     let list = aliases.list();
-    for (name, version) in list {
-        println!("  {} -> {}", name.cyan().bold(), version);
+    for (name, entry) in list {
+        let mut line = format!("  {} -> {}", name.cyan().bold(), entry.target);
+        if entry.tracking {
+            let current = match versions::resolve_alias_installed(name, &aliases, &installed, &available) {
+                Some(version) => version.green().to_string(),
+                None => match versions::resolve_version(name, &available) {
+                    Ok(version) => format!("{} {}", version, t!("alias_not_installed")).yellow().to_string(),
+                    Err(_) => t!("alias_unresolved").yellow().to_string(),
+                },
+            };
+            line = format!("{} {} {}", line, "(tracking) →".bright_black(), current);
+        }
+        if let Some(description) = &entry.description {
+            line = format!("{}  {}", line, format!("# {}", description).bright_black());
+        }
+        println!("{}", line);
     }
 
     println!();
@@ -0,0 +1,300 @@
+// Completions command - Shell completion scripts with dynamic version and alias candidates
+use anyhow::{bail, Result};
+use clap_complete::{generate, Shell};
+use clap_complete_nushell::Nushell;
+
+use crate::config::Config;
+use crate::core::{self, aliases::Aliases, versions};
+
+/// Comando oculto que imprime los candidatos dinámicos, uno por línea
+pub const CANDIDATES_COMMAND: &str = "__complete";
+
+/// Subcomandos cuyo argumento posicional es una versión, con su posición (1 = primer argumento)
+const VERSION_ARGS: [(&str, usize); 7] = [
+    ("install", 1),
+    ("uninstall", 1),
+    ("use", 1),
+    ("info", 1),
+    ("set-default", 1),
+    ("exec", 1),
+    ("alias", 2),
+];
+
+/// Opciones cuyo valor es una versión
+const VERSION_OPTIONS: [&str; 1] = ["--reinstall-packages-from"];
+
+/// `nvm completions <shell>`: script de completado de clap más los candidatos dinámicos
+/// (versiones instaladas, aliases y nombres LTS del índice en caché)
+pub fn print_completions(shell: &str, mut command: clap::Command) -> Result<()> {
+    let mut script = Vec::new();
+    match shell {
+        "bash" => generate(Shell::Bash, &mut command, "nvm", &mut script),
+        "zsh" => generate(Shell::Zsh, &mut command, "nvm", &mut script),
+        "fish" => generate(Shell::Fish, &mut command, "nvm", &mut script),
+        "nu" | "nushell" => generate(Nushell, &mut command, "nvm", &mut script),
+        _ => bail!("Unsupported shell: {}", shell),
+    }
+    let script = String::from_utf8(script)?;
+
+    let script = match shell {
+        "bash" => format!("{}{}", script, bash_candidates()),
+        "zsh" => format!("{}{}", script, zsh_candidates()),
+        "fish" => format!("{}{}", script, fish_candidates()),
+        _ => nushell_candidates(&script),
+    };
+    print!("{}", script);
+    Ok(())
+}
+
+/// `nvm __complete <versions|aliases>`: candidatos sin usar la red; nunca falla
+pub fn print_candidates(kind: &str, config: &Config) {
+    let candidates = match kind {
+        "aliases" => alias_names(),
+        _ => version_candidates(config),
+    };
+    for candidate in candidates {
+        println!("{}", candidate);
+    }
+}
+
+/// Versiones instaladas (más nuevas primero), aliases, `system`, `lts`, `latest` y `lts/<nombre>`
+fn version_candidates(config: &Config) -> Vec<String> {
+    let mut installed = core::get_installed_versions(config).unwrap_or_default();
+    versions::sort_versions(&mut installed);
+
+    let mut candidates = installed;
+    candidates.extend(alias_names());
+    candidates.extend(
+        [versions::SYSTEM_VERSION, "lts", "latest"]
+            .iter()
+            .map(|name| name.to_string()),
+    );
+    candidates.extend(lts_names(&core::read_cached_versions(config)));
+
+    let mut seen = std::collections::HashSet::new();
+    candidates.retain(|candidate| seen.insert(candidate.clone()));
+    candidates
+}
+
+fn alias_names() -> Vec<String> {
+    let mut names: Vec<String> = Aliases::load()
+        .map(|aliases| aliases.aliases.into_keys().collect())
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// `lts/<nombre>` de cada línea LTS del índice, de la más nueva a la más vieja
+fn lts_names(available: &[versions::NodeVersion]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for version in available {
+        if let Some(name) = version.lts.name() {
+            let name = format!("lts/{}", name.to_lowercase());
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// Patrones `<subcomando>:<posición>` para el `case` de bash y zsh (`offset` = índice del subcomando)
+fn version_cases(offset: usize) -> String {
+    VERSION_ARGS
+        .iter()
+        .map(|(name, position)| format!("{}:{}", name, position + offset))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+fn bash_candidates() -> String {
+    format!(
+        r#"
+_nvm_rs_complete() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}" prev="${{COMP_WORDS[COMP_CWORD-1]}}" kind=""
+    case "$prev" in
+        {options}) kind=versions ;;
+    esac
+    if [ -z "$kind" ] && [[ "$cur" != -* ]]; then
+        case "${{COMP_WORDS[1]}}:$COMP_CWORD" in
+            {versions}) kind=versions ;;
+            unalias:2) kind=aliases ;;
+        esac
+    fi
+    if [ -n "$kind" ]; then
+        local IFS=$'\n'
+        COMPREPLY=($(compgen -W "$(command nvm {candidates} "$kind" 2>/dev/null)" -- "$cur"))
+        return 0
+    fi
+    _nvm "$@"
+}}
+if [[ "${{BASH_VERSINFO[0]}}" -eq 4 && "${{BASH_VERSINFO[1]}}" -ge 4 || "${{BASH_VERSINFO[0]}}" -gt 4 ]]; then
+    complete -F _nvm_rs_complete -o nosort -o bashdefault -o default nvm
+else
+    complete -F _nvm_rs_complete -o bashdefault -o default nvm
+fi
+"#,
+        options = VERSION_OPTIONS.join("|"),
+        versions = version_cases(1),
+        candidates = CANDIDATES_COMMAND
+    )
+}
+
+fn zsh_candidates() -> String {
+    format!(
+        r#"
+_nvm_rs_complete() {{
+    local kind=""
+    case "${{words[CURRENT-1]}}" in
+        {options}) kind=versions ;;
+    esac
+    if [[ -z $kind && ${{words[CURRENT]}} != -* ]]; then
+        case "${{words[2]}}:$CURRENT" in
+            {versions}) kind=versions ;;
+            unalias:3) kind=aliases ;;
+        esac
+    fi
+    if [[ -n $kind ]]; then
+        local -a candidates
+        candidates=(${{(f)"$(command nvm {candidates} $kind 2>/dev/null)"}})
+        compadd -V nvm -a candidates
+        return
+    fi
+    _nvm "$@"
+}}
+compdef _nvm_rs_complete nvm
+"#,
+        options = VERSION_OPTIONS.join("|"),
+        versions = version_cases(2),
+        candidates = CANDIDATES_COMMAND
+    )
+}
+
+fn fish_candidates() -> String {
+    let versions = VERSION_ARGS
+        .iter()
+        .map(|(name, position)| format!("{}:{}", name, position + 1))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        r#"
+function __nvm_rs_candidate_kind
+    set -l tokens (commandline -opc)
+    if contains -- "$tokens[-1]" {options}
+        echo versions
+        return 0
+    end
+    switch "$tokens[2]:"(count $tokens)
+        case {versions}
+            echo versions
+        case unalias:2
+            echo aliases
+        case '*'
+            return 1
+    end
+end
+complete -c nvm -f -k -n __nvm_rs_candidate_kind -a "(command nvm {candidates} (__nvm_rs_candidate_kind) 2>/dev/null)"
+"#,
+        options = VERSION_OPTIONS.join(" "),
+        versions = versions,
+        candidates = CANDIDATES_COMMAND
+    )
+}
+
+/// nushell completa con funciones `@"..."` en los parámetros de cada `extern`
+fn nushell_candidates(script: &str) -> String {
+    let mut output = String::new();
+    let mut current: Option<String> = None;
+    let mut positional = 0;
+
+    for line in script.lines() {
+        let trimmed = line.trim_start();
+        if let Some(rest) = trimmed.strip_prefix("export extern ") {
+            // `export extern "nvm use" [`; la raíz (`export extern nvm [`) no tiene candidatos
+            current = rest.split('"').nth(1).map(str::to_string);
+            positional = 0;
+            output.push_str(line);
+            output.push('\n');
+            continue;
+        }
+
+        let mut line = line.to_string();
+        if let Some(subcommand) = current.as_deref().and_then(|c| c.strip_prefix("nvm ")) {
+            let is_positional = !trimmed.starts_with('-') && !trimmed.starts_with(']') && trimmed.contains(": ");
+            if is_positional {
+                positional += 1;
+            }
+            let version_option = VERSION_OPTIONS
+                .iter()
+                .any(|option| trimmed.starts_with(&format!("{}: ", option)));
+            let version_arg = is_positional && VERSION_ARGS.contains(&(subcommand, positional));
+            let kind = if is_positional && subcommand == "unalias" && positional == 1 {
+                Some("aliases")
+            } else if version_arg || version_option {
+                Some("versions")
+            } else {
+                None
+            };
+            if let Some(kind) = kind {
+                line = add_nushell_completer(&line, kind);
+            }
+        }
+        output.push_str(&line);
+        output.push('\n');
+
+        if trimmed.starts_with("module completions {") {
+            for kind in ["versions", "aliases"] {
+                output.push_str(&format!(
+                    "\n  def \"nu-complete nvm {kind}\" [] {{\n    ^nvm {candidates} {kind} | lines\n  }}\n",
+                    kind = kind,
+                    candidates = CANDIDATES_COMMAND
+                ));
+            }
+        }
+    }
+
+    output
+}
+
+/// `version?: string` -> `version?: string@"nu-complete nvm versions"`
+fn add_nushell_completer(line: &str, kind: &str) -> String {
+    let completer = format!("string@\"nu-complete nvm {}\"", kind);
+    match line.find(": string") {
+        Some(index) => format!(
+            "{}: {}{}",
+            &line[..index],
+            completer,
+            &line[index + ": string".len()..]
+        ),
+        None => line.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nushell_candidates_for_version_arguments() {
+        let script = "module completions {\n\n  export extern \"nvm alias\" [\n    --help(-h)                # Print help\n    name: string              # Alias name\n    version: string           # Version to alias\n  ]\n\n  export extern \"nvm unalias\" [\n    name: string              # Alias name to remove\n  ]\n\n}\n";
+        let output = nushell_candidates(script);
+        assert!(output.contains("  def \"nu-complete nvm versions\" [] {\n    ^nvm __complete versions | lines\n  }\n"));
+        assert!(output.contains("    name: string              # Alias name\n"));
+        assert!(output.contains("    version: string@\"nu-complete nvm versions\"           # Version to alias\n"));
+        assert!(output.contains("    name: string@\"nu-complete nvm aliases\"              # Alias name to remove\n"));
+    }
+
+    #[test]
+    fn test_lts_names_from_index() {
+        let available: Vec<versions::NodeVersion> = serde_json::from_str(
+            r#"[
+                {"version": "v22.1.0", "date": "", "files": [], "lts": false},
+                {"version": "v20.11.0", "date": "", "files": [], "lts": "Iron"},
+                {"version": "v20.10.0", "date": "", "files": [], "lts": "Iron"},
+                {"version": "v18.19.0", "date": "", "files": [], "lts": "Hydrogen"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(lts_names(&available), ["lts/iron", "lts/hydrogen"]);
+    }
+}
@@ -3,7 +3,9 @@
 
 use crate::config::Config;
 use crate::core;
+use crate::core::manifest::InstallManifest;
 
+use crate::utils::http::{authorized_get, create_client};
 use crate::utils::{print_check, print_success, print_warning, print_x};
 
 /// Show all Node.js installations found in the system
@@ -32,8 +34,49 @@ pub fn show_system_node() {
     }
 }
 
+/// Audita los manifiestos de instalación: versiones sin manifiesto o sin checksum verificado
+fn check_install_manifests(config: &Config, installed: &[String]) {
+    let mut missing = Vec::new();
+    let mut unverified = Vec::new();
+    for version in installed {
+        match InstallManifest::read(&config.versions_dir().join(version)) {
+            Some(manifest) if manifest.sha256.is_none() => unverified.push(version.as_str()),
+            Some(_) => {}
+            None => missing.push(version.as_str()),
+        }
+    }
+
+    if !missing.is_empty() {
+        print_warning(&format!(
+            "  {} {}",
+            t!("doctor_manifest_missing"),
+            missing.join(", ")
+        ));
+    }
+    if !unverified.is_empty() {
+        print_warning(&format!(
+            "  {} {}",
+            t!("doctor_checksum_unverified"),
+            unverified.join(", ")
+        ));
+    }
+}
+
+/// `prefix` en el .npmrc del usuario: los globales de todas las versiones terminan en el mismo
+/// directorio cuando nvm no fija `npm_config_prefix` (link global sin `nvm env`)
+fn check_npmrc_prefix() {
+    if let Some((path, prefix)) = core::npm::user_npmrc_prefix() {
+        print_warning(&format!(
+            "  {}",
+            t!("doctor_npmrc_prefix")
+                .replace("{file}", &path.display().to_string())
+                .replace("{prefix}", &prefix)
+        ));
+    }
+}
+
 /// Run full doctor diagnostics
-pub fn run_diagnostics(config: &Config, fix: bool) -> Result<()> {
+pub async fn run_diagnostics(config: &Config, fix: bool) -> Result<()> {
     println!("\n{}", t!("doctor_title"));
     println!("{}", "=".repeat(50));
 
@@ -54,17 +97,27 @@ pub fn run_diagnostics(config: &Config, fix: bool) -> Result<()> {
     let installed = core::get_installed_versions(config)?;
     print_check();
     println!("{}: {}", t!("doctor_installed_versions"), installed.len());
+    check_install_manifests(config, &installed);
+    check_npmrc_prefix();
 
-    // Check connectivity (simple test)
+    // Check connectivity: el índice del mirror configurado, con sus credenciales
     print!("{} ", t!("doctor_connectivity"));
-    // Use blocking client to avoid async issues
-    let handle = std::thread::spawn(|| {
-        reqwest::blocking::get("https://nodejs.org")
-    });
-
-    match handle.join() {
-        Ok(Ok(_)) => print_success(&t!("doctor_ok")),
-        _ => print_warning(&t!("doctor_failed")),
+    let url = format!("{}/index.json", config.node_mirror.trim_end_matches('/'));
+    let probe = async {
+        let client = create_client(config)?;
+        authorized_get(&client, &url, config)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok::<(), crate::error::Error>(())
+    };
+
+    match probe.await {
+        Ok(()) => print_success(&t!("doctor_ok")),
+        Err(e) => {
+            print_warning(&t!("doctor_failed"));
+            println!("  {}", e);
+        }
     }
 
     // Check symlink support
@@ -155,6 +208,33 @@ pub fn run_diagnostics(config: &Config, fix: bool) -> Result<()> {
         }
     }
 
+    // Bloque del shell desactualizado (ej: se activó use_on_cd después de instalar)
+    #[cfg(not(windows))]
+    if let Ok((config_path, shell)) = crate::platform::unix::shell::detect_shell_config() {
+        let block = crate::platform::unix::shell::build_shell_block(
+            &config.nvm_dir,
+            shell,
+            crate::platform::unix::shell::ShellBlockOptions::from_config(config),
+        );
+
+        if crate::platform::unix::shell::shell_block_outdated(&config_path, &block) {
+            print!("Shell config ");
+            print_warning(&t!("doctor_shell_block_outdated")
+                .replace("{}", &config_path.display().to_string()));
+
+            if fix {
+                match crate::platform::unix::shell::ensure_shell_block(&config_path, &block) {
+                    Ok(()) => {
+                        print_success(&t!("doctor_updated_config"));
+                        println!("\n  {} Restart your terminal to apply changes", t!("note"));
+                    }
+                    Err(e) => print_warning(&t!("doctor_update_env_failed")
+                        .replace("{}", &e.to_string())),
+                }
+            }
+        }
+    }
+
     println!();
     Ok(())
 }
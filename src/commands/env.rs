@@ -0,0 +1,182 @@
+// Env command - Shell code that pins a Node.js version to the current session
+use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::core::{npm, versions};
+use crate::t;
+use crate::platform::unix::shell::{build_session_env, build_session_wrapper, ShellType};
+
+/// `nvm env`: variables de la sesión con la versión global actual y la función `nvm`
+/// Se usa como `eval "$(nvm env)"` (o `nvm env --shell fish | source`)
+/// nushell, xonsh y elvish no tienen función `nvm`: solo se imprimen las variables
+pub fn print_env(shell: Option<&str>, config: &Config) -> Result<()> {
+    let shell = parse_shell(shell)?;
+
+    // Se fija la versión resuelta, no el link `current`, para que un `nvm use` global
+    // desde otra terminal no cambie esta sesión. Una sesión nueva empieza con la versión
+    // por defecto (`nvm set-default`) si hay una
+    let version = versions::get_session_version(config)
+        .or_else(|| versions::get_default_version(config))
+        .or_else(|| versions::get_current_version(config))
+        .filter(|v| {
+            v == versions::SYSTEM_VERSION
+                || versions::node_binary_path(&config.versions_dir().join(v)).is_file()
+        });
+
+    print!("{}", session_env(shell, version.as_deref(), None, config));
+    print!("{}", build_session_wrapper(shell));
+    Ok(())
+}
+
+/// Código que cambia la versión solo en la sesión actual (`nvm use --shell`)
+/// La versión activa hasta ahora queda en NVM_SESSION_PREVIOUS para `nvm use -`
+pub fn print_use(shell: &str, version: &str, config: &Config) -> Result<()> {
+    let shell = parse_shell(Some(shell))?;
+    let previous = versions::get_active_or_system(config).filter(|previous| previous != version);
+    print!("{}", session_env(shell, Some(version), previous.as_deref(), config));
+    Ok(())
+}
+
+/// `nvm deactivate`: quita de la sesión las rutas de Node.js de nvm (queda el del sistema)
+pub fn print_deactivate(shell: Option<&str>, config: &Config) -> Result<()> {
+    let shell = parse_shell(shell)?;
+    print!("{}", session_env(shell, Some(versions::SYSTEM_VERSION), None, config));
+    eprintln!("✓ {}", t!("nvm_deactivated"));
+    Ok(())
+}
+
+fn parse_shell(shell: Option<&str>) -> Result<ShellType> {
+    match shell {
+        Some(name) => match ShellType::from_name(name) {
+            Some(shell) => Ok(shell),
+            None => bail!("Unsupported shell: {}", name),
+        },
+        None => Ok(ShellType::detect()),
+    }
+}
+
+/// `version` = `SYSTEM_VERSION` quita las rutas de nvm sin agregar ninguna
+fn session_env(
+    shell: ShellType,
+    version: Option<&str>,
+    previous: Option<&str>,
+    config: &Config,
+) -> String {
+    let node_bin = match version {
+        Some(versions::SYSTEM_VERSION) => None,
+        Some(version) => Some(npm::bin_dir(&config.versions_dir().join(version))),
+        None => Some(config.nvm_node_dir()),
+    };
+
+    let mut vars = vec![
+        ("NVM_HOME", config.nvm_dir.to_string_lossy().to_string()),
+        ("NVM_BIN", config.nvm_dir.join("bin").to_string_lossy().to_string()),
+    ];
+    let mut unset = Vec::new();
+    match &node_bin {
+        Some(node_bin) => vars.push(("NVM_NODE", node_bin.to_string_lossy().to_string())),
+        None => unset.push("NVM_NODE"),
+    }
+
+    // npm: prefijo (y caché) de la versión; con el sistema o el link global se quitan
+    match version {
+        Some(version) if version != versions::SYSTEM_VERSION => {
+            let version_dir = config.versions_dir().join(version);
+            for (name, value) in npm::isolation_env(&version_dir, config) {
+                vars.push((name, value.to_string_lossy().to_string()));
+            }
+        }
+        _ => unset.extend(npm::isolation_vars(config)),
+    }
+
+    if let Some(version) = version {
+        vars.push((versions::SESSION_VERSION_VAR, version.to_string()));
+    }
+    if let Some(previous) = previous {
+        vars.push((versions::SESSION_PREVIOUS_VAR, previous.to_string()));
+    }
+
+    let current_path = std::env::var_os("PATH").unwrap_or_default();
+    let path = session_path(
+        std::env::split_paths(&current_path),
+        node_bin.as_deref(),
+        std::env::var_os("NVM_NODE").map(PathBuf::from).as_deref(),
+        config,
+    );
+
+    build_session_env(shell, &vars, &unset, &path)
+}
+
+/// PATH sin las rutas de Node.js que gestiona nvm, con `node_bin` al frente
+/// Sin `node_bin` (Node.js del sistema) también se quitan los shims
+fn session_path(
+    current: impl Iterator<Item = PathBuf>,
+    node_bin: Option<&Path>,
+    previous_node: Option<&Path>,
+    config: &Config,
+) -> Vec<PathBuf> {
+    let versions_dir = config.versions_dir();
+    let global_node = config.nvm_node_dir();
+    let shims_dir = config.shims_dir();
+
+    let mut path: Vec<PathBuf> = node_bin.map(Path::to_path_buf).into_iter().collect();
+    for entry in current {
+        let managed = entry == global_node
+            || entry.starts_with(&versions_dir)
+            || previous_node == Some(entry.as_path())
+            || (node_bin.is_none() && entry == shims_dir);
+        if !managed && !path.contains(&entry) {
+            path.push(entry);
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_session_path_replaces_managed_entries() {
+        let temp = TempDir::new().unwrap();
+        let config = Config::for_tests(temp.path());
+        let v18 = config.versions_dir().join("v18.19.0").join("bin");
+        let v20 = config.versions_dir().join("v20.11.0").join("bin");
+        let custom = PathBuf::from("/opt/node-custom/bin");
+
+        let current = vec![
+            v18.clone(),
+            config.nvm_dir.join("bin"),
+            config.nvm_node_dir(),
+            custom.clone(),
+            PathBuf::from("/usr/bin"),
+        ];
+        let path = session_path(current.clone().into_iter(), Some(&v20), Some(&custom), &config);
+
+        assert_eq!(
+            path,
+            vec![v20, config.nvm_dir.join("bin"), PathBuf::from("/usr/bin")]
+        );
+
+        // Node.js del sistema: sin rutas de versiones ni shims, `nvm` sigue disponible
+        let mut current = current;
+        current.insert(0, config.shims_dir());
+        let path = session_path(current.into_iter(), None, Some(&custom), &config);
+        assert_eq!(path, vec![config.nvm_dir.join("bin"), PathBuf::from("/usr/bin")]);
+    }
+
+    #[test]
+    fn test_session_env_sets_npm_prefix() {
+        let temp = TempDir::new().unwrap();
+        let config = Config::for_tests(temp.path());
+        let version_dir = config.versions_dir().join("v20.11.0");
+
+        let output = session_env(ShellType::Bash, Some("v20.11.0"), None, &config);
+        assert!(output.contains(&format!("export npm_config_prefix='{}'\n", version_dir.display())));
+
+        let output = session_env(ShellType::Bash, Some(versions::SYSTEM_VERSION), None, &config);
+        assert!(output.contains("unset npm_config_prefix\n"));
+    }
+}
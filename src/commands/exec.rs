@@ -0,0 +1,64 @@
+// Exec command - Run a command with an installed Node.js version
+use anyhow::{Context, Result};
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::Config;
+use crate::core::npm;
+
+/// `nvm exec <versión> <comando> [args]`: corre el comando con el bin de la versión primero en el
+/// PATH y el prefijo de npm de esa versión, sin tocar la versión global ni la de la sesión
+pub fn exec(version: &str, command: Vec<OsString>, config: &Config) -> Result<()> {
+    let version = super::install::resolve_installed(version, config)?;
+    let version_dir = config.versions_dir().join(&version);
+
+    let mut paths: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|current| std::env::split_paths(&current).collect())
+        .unwrap_or_default();
+    paths.insert(0, npm::bin_dir(&version_dir));
+
+    let mut command = command.into_iter();
+    let program = command.next().unwrap_or_default();
+    run(&program, command.collect(), paths, Some(&version_dir), config)
+}
+
+/// Ejecuta `program` con el PATH dado; con `version_dir` fija el prefijo (y la caché) de npm de
+/// esa versión, sin ella (Node.js del sistema) los quita. En unix reemplaza el proceso actual
+pub fn run(
+    program: &OsStr,
+    args: Vec<OsString>,
+    paths: Vec<PathBuf>,
+    version_dir: Option<&Path>,
+    config: &Config,
+) -> Result<()> {
+    let mut command = Command::new(program);
+    command.args(args).env("PATH", std::env::join_paths(paths)?);
+    match version_dir {
+        Some(version_dir) => {
+            command.envs(npm::isolation_env(version_dir, config));
+        }
+        None => {
+            for name in npm::isolation_vars(config) {
+                command.env_remove(name);
+            }
+        }
+    }
+
+    let display = Path::new(program).display().to_string();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        let error = command.exec();
+        Err(error).with_context(|| format!("Failed to run {}", display))
+    }
+
+    #[cfg(not(unix))]
+    {
+        let status = command
+            .status()
+            .with_context(|| format!("Failed to run {}", display))?;
+        std::process::exit(status.code().unwrap_or(1));
+    }
+}
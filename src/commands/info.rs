@@ -0,0 +1,57 @@
+// Info command - Show where an installed version came from
+use anyhow::Result;
+use colored::Colorize;
+use crate::t;
+
+use crate::config::Config;
+use crate::core::manifest::InstallManifest;
+
+/// Muestra el manifiesto de instalación de una versión instalada
+pub fn show_info(version: &str, json: bool, config: &Config) -> Result<()> {
+    let version = super::install::resolve_installed(version, config)?;
+    let version_dir = config.versions_dir().join(&version);
+    let manifest = InstallManifest::read(&version_dir);
+
+    if json {
+        let json = serde_json::json!({
+            "version": version,
+            "path": version_dir.to_string_lossy(),
+            "manifest": manifest,
+        });
+        println!("{}", serde_json::to_string_pretty(&json)?);
+        return Ok(());
+    }
+
+    println!("\n{} {}", "Node.js".bold(), version.green().bold());
+    println!("   {} {}", "Path:".bright_black(), version_dir.display());
+
+    let Some(manifest) = manifest else {
+        println!("\n{}", t!("info_no_manifest").yellow());
+        return Ok(());
+    };
+
+    println!("   {} {}", "Source:".bright_black(), manifest.source.as_str());
+    println!("   {} {}", "Location:".bright_black(), manifest.location);
+    if let Some(archive) = &manifest.archive {
+        println!("   {} {}", "Archive:".bright_black(), archive);
+    }
+    if let Some(distribution) = &manifest.distribution {
+        println!("   {} {}", "Distribution:".bright_black(), distribution);
+    }
+    match &manifest.sha256 {
+        Some(sha256) => println!("   {} {}", "SHA256:".bright_black(), sha256),
+        None => println!("   {} {}", "SHA256:".bright_black(), "not verified".yellow()),
+    }
+    if !manifest.configure_flags.is_empty() {
+        println!(
+            "   {} {}",
+            "Configure flags:".bright_black(),
+            manifest.configure_flags.join(" ")
+        );
+    }
+    println!("   {} {}", "Installed:".bright_black(), manifest.installed_date());
+    println!("   {} nvm-rs {}", "Installed by:".bright_black(), manifest.nvm_version);
+    println!();
+
+    Ok(())
+}
@@ -1,11 +1,126 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::process::Command;
 use crate::t;
 
 use crate::config::Config;
+use crate::core::aliases::Aliases;
+use crate::core::archive_cache::ArchiveCache;
+use crate::core::build::{self, BuildOptions};
+use crate::core::lock::NvmLock;
+use crate::core::manifest::{InstallManifest, InstallOrigin, InstallSource};
+use crate::core::npm;
 use crate::core::{download, extract, versions, refresh_installed_cache};
+use crate::utils::auth::redact_url;
 
+/// Pasos opcionales que se ejecutan después de instalar una versión nueva
+#[derive(Debug, Default)]
+pub struct PostInstall {
+    /// No instalar los paquetes de $NVM_HOME/default-packages
+    pub skip_default_packages: bool,
+    /// Versión instalada de la que copiar los paquetes globales
+    pub reinstall_packages_from: Option<String>,
+    /// Reinstalar con la misma versión de cada paquete (nombre@versión)
+    pub keep_package_versions: bool,
+    /// Ejecutar `corepack enable` para la versión nueva
+    pub corepack: bool,
+    /// Preparar el packageManager del package.json más cercano (implica `corepack`)
+    pub corepack_prepare: bool,
+    /// Enlazar con hardlinks los archivos idénticos a los de otras versiones
+    pub dedupe: bool,
+    /// `corepack` se pidió en la línea de comandos (no viene de config.json)
+    pub corepack_requested: bool,
+    /// `dedupe` se pidió en la línea de comandos (no viene de config.json)
+    pub dedupe_requested: bool,
+}
+
+impl PostInstall {
+    /// Resuelve --reinstall-packages-from antes de instalar para fallar sin descargar nada
+    /// `corepack` es `None` si no se indicó en la línea de comandos (se usa config.json)
+    pub fn new(
+        skip_default_packages: bool,
+        reinstall_packages_from: Option<&str>,
+        keep_package_versions: bool,
+        corepack: Option<bool>,
+        corepack_prepare: bool,
+        dedupe: bool,
+        config: &Config,
+    ) -> Result<Self> {
+        let reinstall_packages_from = match reinstall_packages_from {
+            Some(spec) => Some(resolve_installed(spec, config)?),
+            None => None,
+        };
+
+        let corepack_requested = corepack == Some(true) || (corepack != Some(false) && corepack_prepare);
+        let corepack_prepare = corepack != Some(false)
+            && (corepack_prepare || config.corepack_prepare);
+        let corepack = corepack.unwrap_or(config.corepack_enable) || corepack_prepare;
+
+        Ok(PostInstall {
+            skip_default_packages,
+            reinstall_packages_from,
+            keep_package_versions,
+            corepack,
+            corepack_prepare,
+            dedupe: dedupe || config.dedupe_on_install,
+            corepack_requested,
+            dedupe_requested: dedupe,
+        })
+    }
+
+    /// Pasos para una versión que ya estaba instalada: solo los pedidos explícitamente
+    /// (nunca default-packages ni los valores por defecto de config.json); `None` si no hay ninguno
+    fn for_existing(&self) -> Option<PostInstall> {
+        let post = PostInstall {
+            skip_default_packages: true,
+            reinstall_packages_from: self.reinstall_packages_from.clone(),
+            keep_package_versions: self.keep_package_versions,
+            corepack: self.corepack_requested,
+            corepack_prepare: self.corepack_requested && self.corepack_prepare,
+            dedupe: self.dedupe_requested,
+            corepack_requested: self.corepack_requested,
+            dedupe_requested: self.dedupe_requested,
+        };
+        let any = post.reinstall_packages_from.is_some() || post.corepack || post.dedupe;
+        any.then_some(post)
+    }
+}
+
+/// Resuelve una versión instalada: exacta, parcial ("20") o alias
+/// No usa la red: lts/latest se resuelven con el índice en caché si existe
+pub fn resolve_installed(spec: &str, config: &Config) -> Result<String> {
+    let installed = crate::core::get_installed_versions(config)?;
+    if let Some(version) = versions::find_installed_version(spec, &installed) {
+        return Ok(version);
+    }
+
+    let available = crate::core::read_cached_versions(config);
+    let aliases = Aliases::load().unwrap_or_default();
+    if let Some(version) = versions::resolve_alias_installed(spec, &aliases, &installed, &available) {
+        return Ok(version);
+    }
+
+    if let Ok(resolved) = versions::resolve_version(spec, &available) {
+        if let Some(version) = versions::find_installed_version(&resolved, &installed) {
+            return Ok(version);
+        }
+    }
 
-pub async fn install(version: &str, config: &Config) -> Result<()> {
+    bail!("{}", t!("version_not_installed").replace("{}", spec))
+}
+
+/// Como `versions::resolve_version`, pero un alias se resuelve primero contra las versiones
+/// instaladas (un alias que sigue `lts/*` usa la LTS más nueva instalada, no la última publicada)
+pub fn resolve_prefer_installed(spec: &str, available: &[versions::NodeVersion], config: &Config) -> Result<String> {
+    let installed = crate::core::get_installed_versions(config).unwrap_or_default();
+    let aliases = Aliases::load().unwrap_or_default();
+    if let Some(version) = versions::resolve_alias_installed(spec, &aliases, &installed, available) {
+        return Ok(version);
+    }
+    Ok(versions::resolve_version(spec, available)?)
+}
+
+pub async fn install(version: &str, post: &PostInstall, config: &Config) -> Result<()> {
     println!("{} {}", t!("installing_node"), version);
 
     // Obtener lista de versiones disponibles
@@ -22,41 +137,766 @@ pub async fn install(version: &str, config: &Config) -> Result<()> {
 
     println!("Resolved to: {}", node_version.version);
 
-    // Verificar si ya está instalada
+    // Evitar que otro proceso descargue o instale en paralelo
+    let _lock = NvmLock::acquire(config)?;
+
+    // Verificar si ya está instalada (un directorio sin binario es una instalación rota)
     let version_dir = config.versions_dir().join(&node_version.version);
-    if version_dir.exists() {
+    if versions::node_binary_path(&version_dir).is_file() {
         println!("{}", t!("version_already_installed").replace("{}", &node_version.version));
-        return Ok(());
+        return finish_existing(&node_version.version, post, config);
     }
 
-    // Crear directorio temporal para descargas
-    let download_dir = config.nvm_dir.join("cache");
+    // Descargar archivo (o reutilizarlo desde el caché)
+    println!("\n{}", t!("downloading"));
+    let archive_path = download::download_node_archive(node_version, config).await?;
+
+    let mut archive_cache = ArchiveCache::open(config)?;
+    let archive_name = archive_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+
+    let origin = InstallOrigin::new(
+        InstallSource::Mirror,
+        redact_url(&download::get_archive_url(&node_version.version, archive_name, config)),
+        archive_cache.checksum_of(archive_name),
+    );
+    let result = install_archive(&archive_path, Some(&node_version.version), &origin, config);
+
+    // Conservar en el caché solo archivos verificados y respetar el tamaño máximo
+    if !archive_cache.contains(archive_name) && archive_path.exists() {
+        std::fs::remove_file(&archive_path)?;
+    }
+    archive_cache.evict()?;
+
+    finish_staged(result?, post, config)
+}
+
+/// Instala desde un archivo local (`nvm install --from-archive`)
+/// La versión se obtiene del directorio raíz del archivo (ej: node-v20.11.0-linux-x64)
+pub async fn install_from_archive(
+    path: &Path,
+    sha256: Option<&str>,
+    post: &PostInstall,
+    config: &Config,
+) -> Result<()> {
+    if !path.is_file() {
+        bail!("Archive not found: {}", path.display());
+    }
+
+    println!("{}", t!("installing_node").replace("{}", &path.display().to_string()));
+
+    let _lock = NvmLock::acquire(config)?;
+
+    match sha256 {
+        Some(expected) => {
+            println!("Verifying checksum...");
+            download::verify_checksum(path, expected)?;
+            println!("Checksum verified ✓");
+        }
+        None => println!("Warning: no --sha256 given, skipping checksum verification"),
+    }
+
+    let location = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let origin = InstallOrigin::new(InstallSource::Archive, location.display().to_string(), sha256);
+    install_local_archive(path, &origin, post, config)
+}
+
+/// Descarga e instala un archivo desde una URL arbitraria (`nvm install --from-url`)
+pub async fn install_from_url(
+    url: &str,
+    sha256: Option<&str>,
+    post: &PostInstall,
+    config: &Config,
+) -> Result<()> {
+    let filename = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.path_segments()?.next_back().map(str::to_string))
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Cannot determine archive name from URL: {}", url))?;
+
+    println!("{}", t!("installing_node").replace("{}", &filename));
+
+    let _lock = NvmLock::acquire(config)?;
+
+    // Se descarga fuera del caché: el nombre de la URL puede coincidir con un archivo ya
+    // verificado del mirror, y un archivo sin checksum nunca debe quedar con ese nombre
+    let download_dir = config
+        .versions_dir()
+        .join(format!("{}{}{}", STAGING_PREFIX, std::process::id(), DOWNLOAD_SUFFIX));
     std::fs::create_dir_all(&download_dir)?;
+    let archive_path = download_dir.join(&filename);
+
+    let mut archive_cache = ArchiveCache::open(config)?;
+    let result = download_and_install_url(url, &archive_path, &filename, sha256, &mut archive_cache, post, config).await;
 
-    // Descargar archivo
+    let _ = std::fs::remove_dir_all(&download_dir);
+    archive_cache.evict()?;
+
+    result
+}
+
+async fn download_and_install_url(
+    url: &str,
+    archive_path: &Path,
+    filename: &str,
+    sha256: Option<&str>,
+    archive_cache: &mut ArchiveCache,
+    post: &PostInstall,
+    config: &Config,
+) -> Result<()> {
     println!("\n{}", t!("downloading"));
-    let archive_path = download::download_node_archive(node_version, &download_dir, config).await?;
+    download::download_url_to_file(url, archive_path, config).await?;
 
-    // Crear directorio de versiones si no existe
-    let versions_dir = config.versions_dir();
-    std::fs::create_dir_all(&versions_dir)?;
+    match sha256 {
+        Some(expected) => {
+            println!("Verifying checksum...");
+            download::verify_checksum(archive_path, expected)?;
+            println!("Checksum verified ✓");
+
+            // Solo archivos verificados entran al caché, sin reemplazar uno ya registrado
+            if !archive_cache.contains(filename) {
+                std::fs::copy(archive_path, archive_cache.path_for(filename))?;
+                archive_cache.record(filename, expected)?;
+            }
+        }
+        None => println!("Warning: no --sha256 given, skipping checksum verification"),
+    }
+
+    let origin = InstallOrigin::new(InstallSource::Url, redact_url(url), sha256);
+    install_local_archive(archive_path, &origin, post, config)
+}
+
+/// Compila e instala desde el tarball de fuentes (`nvm install --source`)
+/// Usa `node-<v>.tar.gz` del mirror, o un tarball local pasado con --from-archive
+pub async fn install_from_source(
+    version: Option<&str>,
+    local_source: Option<&Path>,
+    sha256: Option<&str>,
+    options: &BuildOptions,
+    post: &PostInstall,
+    config: &Config,
+) -> Result<()> {
+    if cfg!(windows) {
+        bail!("Building Node.js from source is only supported on Linux and macOS");
+    }
 
-    // Extraer archivo
+    let _lock = NvmLock::acquire(config)?;
+    let mut archive_cache = ArchiveCache::open(config)?;
+
+    let (source_archive, expected_version, cached_name, origin) = match (local_source, version) {
+        (Some(path), _) => {
+            if !path.is_file() {
+                bail!("Source archive not found: {}", path.display());
+            }
+            println!("{}", t!("installing_node").replace("{}", &path.display().to_string()));
+            match sha256 {
+                Some(expected) => {
+                    println!("Verifying checksum...");
+                    download::verify_checksum(path, expected)?;
+                    println!("Checksum verified ✓");
+                }
+                None => println!("Warning: no --sha256 given, skipping checksum verification"),
+            }
+            let location = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+            let origin = InstallOrigin::new(InstallSource::Source, location.display().to_string(), sha256);
+            (path.to_path_buf(), None, None, origin)
+        }
+        (None, Some(version)) => {
+            println!("{}", t!("installing_node").replace("{}", version));
+
+            let available_versions = crate::core::get_cached_versions(config).await?;
+            let resolved_version = versions::resolve_version(version, &available_versions)?;
+            println!("Resolved to: {}", resolved_version);
+
+            if versions::node_binary_path(&config.versions_dir().join(&resolved_version)).is_file() {
+                println!("{}", t!("version_already_installed").replace("{}", &resolved_version));
+                return finish_existing(&resolved_version, post, config);
+            }
+
+            let filename = format!("node-{}.tar.gz", resolved_version);
+            let archive_path = archive_cache.path_for(&filename);
+
+            println!("\n{}", t!("downloading"));
+            let url = download::get_archive_url(&resolved_version, &filename, config);
+            download::download_url_to_file(&url, &archive_path, config).await?;
+
+            // --sha256 tiene prioridad sobre SHASUMS256.txt del mirror
+            let expected = match sha256 {
+                Some(expected) => expected.to_string(),
+                None => download::get_expected_checksum(&resolved_version, &filename, config).await?,
+            };
+            println!("Verifying checksum...");
+            if let Err(e) = download::verify_checksum(&archive_path, &expected) {
+                archive_cache.remove(&filename)?;
+                return Err(e.into());
+            }
+            println!("Checksum verified ✓");
+            archive_cache.record(&filename, &expected)?;
+
+            let origin = InstallOrigin::new(InstallSource::Source, redact_url(&url), Some(&expected));
+            (archive_path, Some(resolved_version), Some(filename), origin)
+        }
+        (None, None) => bail!("A version or --from-archive is required with --source"),
+    };
+
+    let result = with_staging_dir(config, |staging_dir, versions_dir| {
+        stage_source_build(
+            &source_archive,
+            staging_dir,
+            versions_dir,
+            expected_version.as_deref(),
+            &origin,
+            options,
+            config,
+        )
+    });
+
+    if cached_name.is_some() {
+        archive_cache.evict()?;
+    }
+
+    let staged = result?;
+    if let Staged::Installed(version) = &staged {
+        println!("Build logs: {}", config.build_logs_dir().join(version).display());
+    }
+
+    finish_staged(staged, post, config)
+}
+
+/// Extrae las fuentes en staging, compila e instala con DESTDIR dentro del staging
+/// y mueve el resultado a versions/<versión>
+fn stage_source_build(
+    source_archive: &Path,
+    staging_dir: &Path,
+    versions_dir: &Path,
+    expected_version: Option<&str>,
+    origin: &InstallOrigin,
+    options: &BuildOptions,
+    config: &Config,
+) -> Result<Staged> {
     println!("\n{}", t!("extracting"));
-    let extracted_path = extract::extract_archive(&archive_path, &versions_dir)?;
+    let source_dir = extract::extract_archive(source_archive, &staging_dir.join("src"))?;
+
+    let version = match expected_version {
+        Some(version) => version.to_string(),
+        None => {
+            let root_name = source_dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default();
+            let version = versions::version_from_archive_root(root_name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Cannot determine Node.js version from source root '{}' (expected node-vX.Y.Z)",
+                    root_name
+                )
+            })?;
+            println!("Detected version: {}", version);
+            version
+        }
+    };
 
-    // Mover al directorio final (versión normalizada)
-    if extracted_path != version_dir {
-        extract::move_extracted_files(&extracted_path, &version_dir)?;
+    let version_dir = versions_dir.join(&version);
+    if versions::node_binary_path(&version_dir).is_file() {
+        println!("{}", t!("version_already_installed").replace("{}", &version));
+        return Ok(Staged::AlreadyInstalled(version));
     }
 
+    // Los logs quedan fuera del staging para poder revisarlos aunque falle
+    let log_dir = config.build_logs_dir().join(&version);
+    println!(
+        "\nBuilding Node.js {} with {} job(s); this can take a while",
+        version, options.jobs
+    );
+    let installed = build::build_node(
+        &source_dir,
+        &version_dir,
+        &staging_dir.join("destdir"),
+        options,
+        &log_dir,
+    )?;
+
+    verify_node_binary(&installed)?;
+
+    let mut manifest = InstallManifest::new(&version, origin);
+    manifest.configure_flags = options.configure_flags.clone();
+    manifest.write(&installed)?;
+
+    if version_dir.exists() {
+        println!("Removing incomplete installation: {}", version_dir.display());
+        std::fs::remove_dir_all(&version_dir)?;
+    }
+
+    extract::move_extracted_files(&installed, &version_dir)?;
+
+    Ok(Staged::Installed(version))
+}
+
+/// Instala un archivo ya descargado cuya versión se toma de su directorio raíz
+fn install_local_archive(
+    archive_path: &Path,
+    origin: &InstallOrigin,
+    post: &PostInstall,
+    config: &Config,
+) -> Result<()> {
+    finish_staged(install_archive(archive_path, None, origin, config)?, post, config)
+}
+
+/// Resultado de instalar desde un archivo o de compilar
+#[derive(Debug, PartialEq)]
+enum Staged {
+    Installed(String),
+    /// La versión ya estaba instalada; no se tocó
+    AlreadyInstalled(String),
+}
+
+fn finish_staged(staged: Staged, post: &PostInstall, config: &Config) -> Result<()> {
+    match staged {
+        Staged::Installed(version) => finish_install(&version, post, config),
+        Staged::AlreadyInstalled(version) => finish_existing(&version, post, config),
+    }
+}
+
+/// La versión ya estaba instalada: solo se ejecutan los pasos pedidos en la línea de comandos
+/// (--reinstall-packages-from, --corepack, --dedupe); default-packages no se reinstala
+fn finish_existing(version: &str, post: &PostInstall, config: &Config) -> Result<()> {
+    let Some(post) = post.for_existing() else {
+        return Ok(());
+    };
+    println!("{}", t!("post_install_existing").replace("{}", version));
+    run_post_install(version, &post, config)
+}
+
+/// Actualiza el caché de instaladas, informa y ejecuta los pasos post-instalación
+/// Los fallos de los pasos opcionales se informan pero no deshacen la instalación
+fn finish_install(version: &str, post: &PostInstall, config: &Config) -> Result<()> {
     // Actualizar cache de versiones instaladas
     refresh_installed_cache(config)?;
 
-    // Eliminar archivo de descarga
-    std::fs::remove_file(&archive_path)?;
+    println!("\n✓ {}", t!("installed_node").replace("{}", version));
+
+    run_post_install(version, post, config)
+}
+
+/// Pasos post-instalación sobre una versión instalada
+fn run_post_install(version: &str, post: &PostInstall, config: &Config) -> Result<()> {
+    let version_dir = config.versions_dir().join(version);
+
+    if let Some(source) = &post.reinstall_packages_from {
+        reinstall_packages(source, version, post.keep_package_versions, config)?;
+    }
+
+    if !post.skip_default_packages {
+        install_default_packages(&version_dir, config)?;
+    }
+
+    if post.corepack {
+        setup_corepack(&version_dir, version, post.corepack_prepare);
+    }
+
+    // Al final, para incluir los paquetes globales recién instalados
+    if post.dedupe {
+        if let Err(e) = super::misc::dedupe_installed(config) {
+            eprintln!("{}", t!("dedupe_failed").replace("{}", &e.to_string()));
+        }
+    }
+
+    // Shims para los binarios de los paquetes globales recién instalados
+    if config.shims {
+        if let Err(e) = super::shims::regenerate_shims(config) {
+            eprintln!("{}", t!("shims_failed").replace("{}", &e.to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Habilita los shims de corepack y, si se pide, prepara el gestor del proyecto actual
+fn setup_corepack(version_dir: &Path, version: &str, prepare: bool) {
+    if !npm::has_corepack(version_dir) {
+        eprintln!("{}", t!("corepack_not_bundled").replace("{}", version));
+        return;
+    }
+
+    println!("\n{}", t!("corepack_enabling"));
+    if let Err(e) = npm::corepack_enable(version_dir) {
+        eprintln!("  ✗ corepack enable: {}", e);
+        return;
+    }
+    println!("  ✓ corepack enable");
+
+    if !prepare {
+        return;
+    }
+
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let Some((manifest, spec)) = npm::find_package_manager(&cwd) else {
+        println!("  {}", t!("corepack_no_package_manager"));
+        return;
+    };
+
+    println!("  {} ({})", spec, manifest.display());
+    match npm::corepack_prepare(version_dir, &spec) {
+        Ok(()) => println!("  ✓ corepack prepare {}", spec),
+        Err(e) => eprintln!("  ✗ corepack prepare {}: {}", spec, e),
+    }
+}
+
+/// Instala los paquetes listados en $NVM_HOME/default-packages con el npm de la versión
+fn install_default_packages(version_dir: &Path, config: &Config) -> Result<()> {
+    let packages = npm::read_default_packages(&config.default_packages_file())?;
+    if packages.is_empty() {
+        return Ok(());
+    }
+
+    println!("\n{}", t!("default_packages_installing").replace("{}", &packages.len().to_string()));
+    install_packages(version_dir, &packages);
+
+    Ok(())
+}
+
+/// Copia los paquetes globales de otra versión instalada (excepto los incluidos con Node.js)
+fn reinstall_packages(source: &str, target: &str, keep_versions: bool, config: &Config) -> Result<()> {
+    if source == target {
+        return Ok(());
+    }
+
+    let packages = npm::list_global_packages(&config.versions_dir().join(source))?;
+    let mut specs = Vec::new();
+    for package in packages {
+        if npm::BUNDLED_PACKAGES.contains(&package.name.as_str()) {
+            continue;
+        }
+        if package.linked {
+            println!("  - {} (linked with `npm link`, skipped)", package.name);
+            continue;
+        }
+        specs.push(package.spec(keep_versions));
+    }
+
+    if specs.is_empty() {
+        println!("\n{}", t!("reinstall_packages_none").replace("{}", source));
+        return Ok(());
+    }
+
+    println!(
+        "\n{}",
+        t!("reinstall_packages_from")
+            .replace("{count}", &specs.len().to_string())
+            .replace("{version}", source)
+    );
+    install_packages(&config.versions_dir().join(target), &specs);
+
+    Ok(())
+}
+
+/// Instala paquetes globales uno por uno para poder informar cada fallo por separado
+fn install_packages(version_dir: &Path, packages: &[String]) {
+    let mut failed = 0;
+    for package in packages {
+        match npm::install_global(version_dir, package) {
+            Ok(()) => println!("  ✓ {}", package),
+            Err(e) => {
+                failed += 1;
+                eprintln!("  ✗ {}: {}", package, e);
+            }
+        }
+    }
+
+    if failed > 0 {
+        eprintln!("{}", t!("packages_failed").replace("{}", &failed.to_string()));
+    }
+}
+
+/// Extrae en un directorio oculto de staging, verifica `node` y lo renombra a versions/<versión>
+/// Si algo falla el staging se elimina, así una instalación rota nunca aparece como instalada
+/// Devuelve la versión instalada, o `Staged::AlreadyInstalled` si ya estaba instalada
+fn install_archive(
+    archive_path: &Path,
+    expected_version: Option<&str>,
+    origin: &InstallOrigin,
+    config: &Config,
+) -> Result<Staged> {
+    with_staging_dir(config, |staging_dir, versions_dir| {
+        println!("\n{}", t!("extracting"));
+        stage_archive(archive_path, staging_dir, versions_dir, expected_version, origin)
+    })
+}
+
+/// Ejecuta `f` con un directorio de staging dentro de versions/ que se elimina siempre al terminar
+/// Estar dentro de versions/ hace que el rename final sea atómico (mismo sistema de archivos)
+fn with_staging_dir<T>(
+    config: &Config,
+    f: impl FnOnce(&Path, &Path) -> Result<T>,
+) -> Result<T> {
+    let versions_dir = config.versions_dir();
+    std::fs::create_dir_all(&versions_dir)?;
+
+    // Con el lock tomado, cualquier otro staging es de una instalación interrumpida
+    // (se conserva solo la descarga de `--from-url` de este proceso)
+    let download_dir = format!("{}{}{}", STAGING_PREFIX, std::process::id(), DOWNLOAD_SUFFIX);
+    remove_staging_dirs(&versions_dir, &download_dir)?;
+
+    let staging_dir = versions_dir.join(format!("{}{}", STAGING_PREFIX, std::process::id()));
+
+    let result = f(&staging_dir, &versions_dir);
+
+    if staging_dir.exists() {
+        let _ = std::fs::remove_dir_all(&staging_dir);
+    }
+
+    result
+}
+
+/// Elimina los directorios `.staging-*` de versions/ salvo `keep`
+fn remove_staging_dirs(versions_dir: &Path, keep: &str) -> Result<()> {
+    for entry in std::fs::read_dir(versions_dir)?.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with(STAGING_PREFIX) && name != keep {
+            let path = entry.path();
+            let removed = if path.is_dir() {
+                std::fs::remove_dir_all(&path)
+            } else {
+                std::fs::remove_file(&path)
+            };
+            removed.with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Prefijo de los directorios de staging (ocultos para `get_installed_versions`)
+const STAGING_PREFIX: &str = ".staging-";
+
+/// Sufijo del directorio de staging donde `--from-url` descarga el archivo
+const DOWNLOAD_SUFFIX: &str = "-download";
+
+fn stage_archive(
+    archive_path: &Path,
+    staging_dir: &Path,
+    versions_dir: &Path,
+    expected_version: Option<&str>,
+    origin: &InstallOrigin,
+) -> Result<Staged> {
+    let extracted_path = extract::extract_archive(archive_path, staging_dir)?;
+
+    let version = match expected_version {
+        Some(version) => version.to_string(),
+        None => {
+            let root_name = extracted_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default();
+            let version = versions::version_from_archive_root(root_name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Cannot determine Node.js version from archive root '{}' (expected node-vX.Y.Z-<platform>)",
+                    root_name
+                )
+            })?;
+            println!("Detected version: {}", version);
+            version
+        }
+    };
+
+    let version_dir = versions_dir.join(&version);
+    if versions::node_binary_path(&version_dir).is_file() {
+        println!("{}", t!("version_already_installed").replace("{}", &version));
+        return Ok(Staged::AlreadyInstalled(version));
+    }
+
+    verify_node_binary(&extracted_path)?;
+
+    // El manifiesto se escribe antes del rename para que llegue junto con la versión
+    InstallManifest::new(&version, origin).write(&extracted_path)?;
+
+    // Restos de una instalación interrumpida antes de usar staging
+    if version_dir.exists() {
+        println!("Removing incomplete installation: {}", version_dir.display());
+        std::fs::remove_dir_all(&version_dir)?;
+    }
+
+    extract::move_extracted_files(&extracted_path, &version_dir)?;
+
+    Ok(Staged::Installed(version))
+}
+
+/// Comprueba que el directorio extraído contiene un `node` que se puede ejecutar
+fn verify_node_binary(extracted_path: &Path) -> Result<()> {
+    let node = versions::node_binary_path(extracted_path);
+    if !node.is_file() {
+        bail!(
+            "Archive does not contain a Node.js binary (missing {})",
+            node.strip_prefix(extracted_path).unwrap_or(&node).display()
+        );
+    }
+
+    let output = Command::new(&node)
+        .arg("--version")
+        .output()
+        .with_context(|| format!("Failed to run {}", node.display()))?;
+
+    if !output.status.success() {
+        bail!(
+            "Extracted node binary does not run: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
 
-    println!("\n✓ {}", t!("installed_node").replace("{}", &node_version.version));
+    println!("Verified node {}", String::from_utf8_lossy(&output.stdout).trim());
 
     Ok(())
 }
+
+#[cfg(all(test, not(target_os = "windows")))]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    /// Crea un .tar.gz con `<root>/bin/node`; si `working` es false, `node` falla al ejecutarse
+    fn build_archive(path: &Path, root: &str, working: bool) {
+        let mut builder = tar::Builder::new(Vec::new());
+        let content: &[u8] = if working {
+            b"#!/bin/sh\necho v20.11.0\n"
+        } else {
+            b"#!/bin/sh\necho 'cannot execute binary file' >&2\nexit 126\n"
+        };
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, format!("{}/bin/node", root), content)
+            .unwrap();
+        let data = builder.into_inner().unwrap();
+
+        let file = std::fs::File::create(path).unwrap();
+        let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        encoder.write_all(&data).unwrap();
+        encoder.finish().unwrap();
+    }
+
+    fn test_config(temp: &TempDir) -> Config {
+        Config::for_tests(temp.path())
+    }
+
+    fn origin() -> InstallOrigin {
+        InstallOrigin::new(InstallSource::Archive, "/tmp/node-v20.11.0-linux-x64.tar.gz", Some("abc123"))
+    }
+
+    #[test]
+    fn test_install_archive_removes_orphaned_staging_dirs() {
+        let temp = TempDir::new().unwrap();
+        let config = test_config(&temp);
+        let orphan = config.versions_dir().join(format!("{}999999", STAGING_PREFIX));
+        std::fs::create_dir_all(orphan.join("node-v18.0.0-linux-x64")).unwrap();
+
+        let archive = temp.path().join("node-v20.11.0-linux-x64.tar.gz");
+        build_archive(&archive, "node-v20.11.0-linux-x64", true);
+        install_archive(&archive, None, &origin(), &config).unwrap();
+
+        assert!(!orphan.exists());
+        assert_eq!(crate::core::get_installed_versions(&config).unwrap(), ["v20.11.0"]);
+    }
+
+    #[test]
+    fn test_install_archive_detects_version() {
+        let temp = TempDir::new().unwrap();
+        let config = test_config(&temp);
+
+        let archive = temp.path().join("node-v20.11.0-linux-x64.tar.gz");
+        build_archive(&archive, "node-v20.11.0-linux-x64", true);
+
+        let version = install_archive(&archive, None, &origin(), &config).unwrap();
+        assert_eq!(version, Staged::Installed("v20.11.0".to_string()));
+        let version_dir = config.versions_dir().join("v20.11.0");
+        assert!(version_dir.join("bin").join("node").is_file());
+
+        let manifest = InstallManifest::read(&version_dir).unwrap();
+        assert_eq!(manifest.source, InstallSource::Archive);
+        assert_eq!(manifest.sha256.as_deref(), Some("abc123"));
+        assert_eq!(manifest.distribution.as_deref(), Some("linux-x64"));
+
+        // Una segunda instalación no sobrescribe la existente
+        let version = install_archive(&archive, None, &origin(), &config).unwrap();
+        assert_eq!(version, Staged::AlreadyInstalled("v20.11.0".to_string()));
+        assert_no_staging_left(&config);
+    }
+
+    #[test]
+    fn test_install_archive_rejects_unknown_root() {
+        let temp = TempDir::new().unwrap();
+        let config = test_config(&temp);
+
+        let archive = temp.path().join("custom.tar.gz");
+        build_archive(&archive, "package", true);
+
+        assert!(install_archive(&archive, None, &origin(), &config).is_err());
+        assert_no_staging_left(&config);
+    }
+
+    #[test]
+    fn test_broken_binary_is_never_installed() {
+        let temp = TempDir::new().unwrap();
+        let config = test_config(&temp);
+
+        let archive = temp.path().join("node-v20.11.0-linux-x64.tar.gz");
+        build_archive(&archive, "node-v20.11.0-linux-x64", false);
+
+        assert!(install_archive(&archive, Some("v20.11.0"), &origin(), &config).is_err());
+        assert!(!config.versions_dir().join("v20.11.0").exists());
+        assert_no_staging_left(&config);
+    }
+
+    #[test]
+    fn test_incomplete_installation_is_replaced() {
+        let temp = TempDir::new().unwrap();
+        let config = test_config(&temp);
+
+        // Directorio a medio poblar dejado por una versión anterior
+        let leftover = config.versions_dir().join("v20.11.0").join("lib");
+        std::fs::create_dir_all(&leftover).unwrap();
+
+        let archive = temp.path().join("node-v20.11.0-linux-x64.tar.gz");
+        build_archive(&archive, "node-v20.11.0-linux-x64", true);
+
+        let version = install_archive(&archive, Some("v20.11.0"), &origin(), &config).unwrap();
+        assert_eq!(version, Staged::Installed("v20.11.0".to_string()));
+        assert!(!leftover.exists());
+        assert!(config.versions_dir().join("v20.11.0").join("bin").join("node").is_file());
+    }
+
+    #[test]
+    fn test_existing_version_skips_default_packages() {
+        let temp = TempDir::new().unwrap();
+        let mut config = test_config(&temp);
+        config.corepack_enable = true;
+        config.dedupe_on_install = true;
+        // Un default-packages ilegible hace fallar cualquier intento de leerlo
+        std::fs::create_dir_all(config.default_packages_file()).unwrap();
+
+        let post = PostInstall::new(false, None, false, None, false, false, &config).unwrap();
+        assert!(run_post_install("v20.11.0", &post, &config).is_err());
+
+        // Sin pasos pedidos en la línea de comandos no se toca nada, tampoco config.json
+        assert!(post.for_existing().is_none());
+        finish_existing("v20.11.0", &post, &config).unwrap();
+
+        let post = PostInstall::new(false, None, false, None, false, true, &config).unwrap();
+        let existing = post.for_existing().unwrap();
+        assert!(existing.skip_default_packages);
+        assert!(existing.dedupe);
+        assert!(!existing.corepack);
+    }
+
+    fn assert_no_staging_left(config: &Config) {
+        let leftovers: Vec<_> = std::fs::read_dir(config.versions_dir())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with(STAGING_PREFIX))
+            .collect();
+        assert!(leftovers.is_empty(), "staging directories left behind");
+    }
+}
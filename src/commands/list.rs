@@ -1,17 +1,28 @@
 use anyhow::Result;
+use colored::Colorize;
 use crate::t;
 
+use serde::Serialize;
 use std::collections::BTreeMap;
+use std::path::PathBuf;
 use crate::config::Config;
-use crate::core::{self, versions};
+use crate::core::manifest::{InstallManifest, InstallSource};
+use crate::core::{self, detection, shims, versions};
 
 
+/// Marca de la versión por defecto en `nvm ls` y `nvm current`
+const DEFAULT_MARKER: &str = "(default)";
+
 /// List installed Node.js versions
 pub async fn list_installed(config: &Config) -> Result<()> {
     // Obtener versiones instaladas
     let mut installed = core::get_installed_versions(config)?;
 
-    if installed.is_empty() {
+    // Obtener versión actual (la de la sesión del shell si hay una) y la versión por defecto
+    let current = versions::get_active_or_system(config);
+    let default_version = versions::get_default_version(config);
+
+    if installed.is_empty() && current.is_none() {
         println!("{}", t!("no_versions_installed"));
         return Ok(());
     }
@@ -19,9 +30,6 @@ pub async fn list_installed(config: &Config) -> Result<()> {
     // Ordenar versiones
     versions::sort_versions(&mut installed);
 
-    // Obtener versión actual
-    let current = versions::get_current_version(config);
-
     // Obtener información de versiones remotas para LTS info
     let available_versions = core::get_cached_versions(config).await.unwrap_or_default();
 
@@ -31,8 +39,18 @@ pub async fn list_installed(config: &Config) -> Result<()> {
     // Mostrar cada versión
     for version in &installed {
         let is_current = current.as_ref().map(|c| c == version).unwrap_or(false);
-        let formatted = versions::format_installed_version(version, is_current, &available_versions);
-        println!("{}", formatted);
+        let mut formatted = versions::format_installed_version(version, is_current, &available_versions);
+        if default_version.as_ref() == Some(version) {
+            formatted = format!("{} {}", formatted, DEFAULT_MARKER.blue());
+        }
+        match InstallManifest::read(&config.versions_dir().join(version)) {
+            Some(manifest) => println!("{} {}", formatted, manifest_summary(&manifest).bright_black()),
+            None => println!("{}", formatted),
+        }
+    }
+
+    if current.as_deref() == Some(versions::SYSTEM_VERSION) {
+        println!("{}", system_entry(config));
     }
 
     // Mostrar versión actual al final
@@ -43,6 +61,23 @@ pub async fn list_installed(config: &Config) -> Result<()> {
     Ok(())
 }
 
+/// Línea de `nvm ls` para el Node.js del sistema cuando está en uso
+fn system_entry(config: &Config) -> String {
+    let detail = detection::find_system_node(config)
+        .map(|info| format!(" {}", format!("({} {})", info.version, info.path.display()).bright_black()))
+        .unwrap_or_default();
+    format!("{} {}{}", "▶".green().bold(), versions::SYSTEM_VERSION.green().bold(), detail)
+}
+
+/// Origen y fecha de instalación; el origen solo se muestra si no es el mirror
+fn manifest_summary(manifest: &InstallManifest) -> String {
+    if manifest.source == InstallSource::Mirror {
+        format!("installed {}", manifest.installed_date())
+    } else {
+        format!("installed {} from {}", manifest.installed_date(), manifest.source.as_str())
+    }
+}
+
 /// List remote versions available for download
 pub async fn list_remote(lts: bool, config: &Config) -> Result<()> {
     use versions::VersionFilter;
@@ -127,12 +162,236 @@ pub async fn list_remote(lts: bool, config: &Config) -> Result<()> {
 }
 
 /// Show current active version
-pub fn show_current(config: &Config) -> Result<()> {
-    if let Some(current_version) = versions::get_current_version(config) {
-        println!("{}", current_version);
+pub fn show_current(verbose: bool, json: bool, config: &Config) -> Result<()> {
+    if verbose || json {
+        return show_current_verbose(json, config);
+    }
+
+    if let Some(current_version) = versions::get_active_or_system(config) {
+        // En una terminal se indica si es la versión por defecto; en `$(nvm current)` solo la versión
+        let is_default = versions::get_default_version(config).as_ref() == Some(&current_version);
+        if is_default && atty::is(atty::Stream::Stdout) {
+            println!("{} {}", current_version, DEFAULT_MARKER.bright_black());
+        } else {
+            println!("{}", current_version);
+        }
     } else {
         println!("{}", t!("no_current_version"));
     }
 
     Ok(())
 }
+
+/// De dónde sale la versión activa
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum ActiveSource {
+    /// Fijada en la sesión del shell (`nvm env`, `nvm use` con la función del shell)
+    Session { env_var: String },
+    /// Archivo de versión, resuelto por los shims
+    VersionFile { file: PathBuf, spec: String, alias_chain: Vec<String> },
+    /// Alias `default`, resuelto por los shims
+    DefaultAlias { alias_chain: Vec<String> },
+    /// Link global `current`
+    Global { link: PathBuf },
+    /// Node.js del sistema, fuera de nvm
+    System,
+    /// Los shims están primeros en el PATH pero no pueden resolver una versión
+    /// (ej: el archivo de versión pide una que no está instalada)
+    Unresolved { error: String },
+}
+
+/// Archivo de versión más cercano y la versión instalada que pide
+#[derive(Debug, Serialize)]
+struct VersionFileInfo {
+    file: PathBuf,
+    spec: String,
+    resolved: Option<String>,
+}
+
+/// `nvm current --verbose [--json]`
+#[derive(Debug, Serialize)]
+struct CurrentReport {
+    /// `None` si la fuente es `Unresolved`
+    version: Option<String>,
+    source: ActiveSource,
+    default: bool,
+    binary: Option<PathBuf>,
+    path_node: Option<PathBuf>,
+    path_matches: bool,
+    version_file: Option<VersionFileInfo>,
+}
+
+/// Versión activa y su origen: con los shims primeros en el PATH, la que resuelven ellos;
+/// si no, la de la sesión, la global o la del sistema (igual que `nvm current`)
+fn current_report(config: &Config) -> Option<CurrentReport> {
+    let path_node = std::env::var_os("PATH")
+        .and_then(|path| std::env::split_paths(&path).find_map(|dir| shims::find_tool(&dir, "node")));
+    let via_shim = path_node
+        .as_ref()
+        .is_some_and(|node| node.starts_with(config.shims_dir()));
+
+    let version_file = versions::find_nvmrc_in_tree(None).map(|(file, spec)| {
+        let resolved = if spec == versions::SYSTEM_VERSION {
+            Some(spec.clone())
+        } else {
+            super::install::resolve_installed(&spec, config).ok()
+        };
+        VersionFileInfo { file, spec, resolved }
+    });
+
+    // Con los shims primeros, su error es lo que verá quien ejecute `node`: se informa tal cual
+    let shim_resolution = via_shim.then(|| super::shims::shim_source(config));
+    if let Some(Err(error)) = shim_resolution {
+        return Some(CurrentReport {
+            version: None,
+            source: ActiveSource::Unresolved { error: format!("{:#}", error) },
+            default: false,
+            binary: None,
+            path_node,
+            path_matches: false,
+            version_file,
+        });
+    }
+
+    let (version, source) = shim_resolution
+        .and_then(Result::ok)
+        .or_else(|| {
+            versions::get_session_version(config).map(|version| {
+                let env_var = versions::SESSION_VERSION_VAR.to_string();
+                (version, ActiveSource::Session { env_var })
+            })
+        })
+        .or_else(|| {
+            versions::get_current_version(config)
+                .map(|version| (version, ActiveSource::Global { link: config.current_dir() }))
+        })
+        .or_else(|| {
+            detection::find_system_node(config)
+                .map(|_| (versions::SYSTEM_VERSION.to_string(), ActiveSource::System))
+        })?;
+
+    let binary = if version == versions::SYSTEM_VERSION {
+        detection::find_system_node(config).map(|info| info.path)
+    } else {
+        Some(versions::node_binary_path(&config.versions_dir().join(&version)))
+    };
+    let same_file = |a: &PathBuf, b: &PathBuf| match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    };
+    let path_matches = via_shim
+        || matches!((&path_node, &binary), (Some(node), Some(binary)) if same_file(node, binary));
+
+    Some(CurrentReport {
+        default: versions::get_default_version(config).as_ref() == Some(&version),
+        version: Some(version),
+        source,
+        binary,
+        path_node,
+        path_matches,
+        version_file,
+    })
+}
+
+fn show_current_verbose(json: bool, config: &Config) -> Result<()> {
+    let report = current_report(config);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    let Some(report) = report else {
+        println!("{}", t!("no_current_version"));
+        return Ok(());
+    };
+
+    let version = report.version.as_deref().unwrap_or_default();
+    match &report.version {
+        Some(version) if report.default => {
+            println!("\n{} {}", version.green().bold(), DEFAULT_MARKER.blue())
+        }
+        Some(version) => println!("\n{}", version.green().bold()),
+        None => println!("\n{}", "unresolved".red().bold()),
+    }
+    println!("   {} {}", "Source:".bright_black(), describe_source(&report.source, version));
+
+    let binary = report
+        .binary
+        .as_ref()
+        .map(|binary| binary.display().to_string())
+        .unwrap_or_else(|| "-".to_string());
+    println!("   {} {}", "Binary:".bright_black(), binary);
+
+    match &report.path_node {
+        Some(node) if report.path_matches => {
+            println!("   {} {} {}", "PATH node:".bright_black(), node.display(), "✓".green())
+        }
+        Some(node) => println!("   {} {} {}", "PATH node:".bright_black(), node.display(), "✗".red()),
+        None => println!("   {} {}", "PATH node:".bright_black(), "not found".yellow()),
+    }
+
+    let version_file = report
+        .version_file
+        .as_ref()
+        .filter(|_| !matches!(report.source, ActiveSource::VersionFile { .. }));
+    if let Some(file) = version_file {
+        let resolved = file.resolved.as_deref().unwrap_or("not installed");
+        println!(
+            "   {} {} ({} → {})",
+            "Version file:".bright_black(),
+            file.file.display(),
+            file.spec,
+            resolved
+        );
+        if report.version.is_some() && file.resolved != report.version {
+            println!("   {}", t!("current_version_file_inactive").yellow());
+        }
+    }
+
+    if !report.path_matches && report.version.is_some() {
+        println!("\n{}", t!("current_path_mismatch").yellow());
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Descripción del origen para la salida de texto
+fn describe_source(source: &ActiveSource, version: &str) -> String {
+    match source {
+        ActiveSource::Session { env_var } => format!("shell session (${})", env_var),
+        ActiveSource::VersionFile { file, alias_chain, .. } => {
+            format!("{} ({})", file.display(), chain_display(alias_chain, version))
+        }
+        ActiveSource::DefaultAlias { alias_chain } => {
+            format!("default alias ({})", chain_display(alias_chain, version))
+        }
+        ActiveSource::Global { link } => format!("global version ({})", link.display()),
+        ActiveSource::System => "system Node.js (outside nvm)".to_string(),
+        ActiveSource::Unresolved { error } => format!("shims ({})", error.red()),
+    }
+}
+
+/// `default → 20 → v20.11.0`; la versión resuelta se agrega si la cadena no termina en ella
+fn chain_display(alias_chain: &[String], version: &str) -> String {
+    let mut chain = alias_chain.to_vec();
+    if chain.last().map(String::as_str) != Some(version) {
+        chain.push(version.to_string());
+    }
+    chain.join(" → ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_display() {
+        let chain = vec!["default".to_string(), "20".to_string()];
+        assert_eq!(chain_display(&chain, "v20.11.0"), "default → 20 → v20.11.0");
+
+        let chain = vec!["v20.11.0".to_string()];
+        assert_eq!(chain_display(&chain, "v20.11.0"), "v20.11.0");
+    }
+}
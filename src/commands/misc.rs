@@ -4,8 +4,47 @@
 use std::io::{self, Write};
 use crate::config::Config;
 use crate::core::{self, versions, refresh_installed_cache};
+use crate::core::aliases::{Aliases, DEFAULT_ALIAS};
+use crate::core::dedupe;
+use crate::core::lock::NvmLock;
+use crate::core::state::NvmState;
 use crate::i18n::{set_locale, Locale};
 
+/// Replace identical files across installed versions with hardlinks
+pub fn dedupe(config: &Config) -> Result<()> {
+    let _lock = NvmLock::acquire(config)?;
+    dedupe_installed(config)
+}
+
+/// Deduplica todas las versiones instaladas; quien llama debe tener el lock de $NVM_HOME
+pub fn dedupe_installed(config: &Config) -> Result<()> {
+    let installed = core::get_installed_versions(config)?;
+    if installed.len() < 2 {
+        println!("{}", t!("dedupe_nothing"));
+        return Ok(());
+    }
+
+    println!("\n{}", t!("dedupe_scanning").replace("{}", &installed.len().to_string()));
+    let dirs: Vec<_> = installed
+        .iter()
+        .map(|version| config.versions_dir().join(version))
+        .collect();
+    let report = dedupe::dedupe_dirs(&dirs)?;
+
+    for (path, error) in &report.failures {
+        eprintln!("  ✗ {}: {}", path.display(), error);
+    }
+
+    println!(
+        "{}",
+        t!("dedupe_complete")
+            .replace("{files}", &report.files_linked.to_string())
+            .replace("{size}", &super::stats::format_size(report.bytes_saved))
+    );
+
+    Ok(())
+}
+
 /// Remove unused versions (keep current and LTS)
 pub async fn cleanup(yes: bool, config: &Config) -> Result<()> {
     println!("{}", t!("cleaning_up"));
@@ -18,8 +57,9 @@ pub async fn cleanup(yes: bool, config: &Config) -> Result<()> {
         return Ok(());
     }
 
-    // Obtener versión actual
+    // Obtener versión actual y la versión por defecto
     let current_version = versions::get_current_version(config);
+    let default_version = versions::get_default_version(config);
 
     // Obtener información de versiones remotas para identificar LTS
     let available_versions = core::get_cached_versions(config).await.unwrap_or_default();
@@ -38,6 +78,11 @@ pub async fn cleanup(yes: bool, config: &Config) -> Result<()> {
             }
         }
 
+        // Mantener la versión por defecto
+        if default_version.as_ref() == Some(version) {
+            keep = true;
+        }
+
         // Mantener versiones LTS
         if let Some(node_version) = available_versions.iter().find(|v| &v.version == version) {
             if node_version.lts.is_lts() {
@@ -69,6 +114,10 @@ pub async fn cleanup(yes: bool, config: &Config) -> Result<()> {
         println!("  {} ", t!("cleanup_current_version")
             .replace("{version}", current));
     }
+    if let Some(ref default) = default_version {
+        println!("  {} ", t!("cleanup_default_version")
+            .replace("{version}", default));
+    }
     let lts_count = versions_to_keep.iter()
         .filter(|v| {
             available_versions.iter()
@@ -98,6 +147,7 @@ pub async fn cleanup(yes: bool, config: &Config) -> Result<()> {
     }
 
     // Eliminar versiones
+    let _lock = NvmLock::acquire(config)?;
     let mut removed_count = 0;
     for version in &versions_to_remove {
         let version_dir = config.versions_dir().join(version);
@@ -116,10 +166,26 @@ pub async fn cleanup(yes: bool, config: &Config) -> Result<()> {
     Ok(())
 }
 
-/// Set default version for new shells (placeholder)
-pub fn set_default(version: String) -> Result<()> {
-    println!("Command 'set-default {}' - Not yet implemented", version);
-    println!("This will be implemented in Phase 7 of the migration plan");
+/// `nvm set-default`: guarda la versión en el alias `default`
+/// La usan las sesiones nuevas de `nvm env`, los shims, `nvm use` sin .nvmrc y `nvm uninstall`
+/// al quitar la versión actual; si no hay versión global activa, también se activa
+pub fn set_default(version: &str, config: &Config) -> Result<()> {
+    let resolved_version = super::install::resolve_installed(version, config)?;
+
+    let _lock = NvmLock::acquire(config)?;
+    let mut aliases = Aliases::load()?;
+    aliases.set(DEFAULT_ALIAS.to_string(), resolved_version.clone());
+    aliases.save()?;
+
+    println!("✓ {}", t!("default_version_set").replace("{}", &resolved_version));
+
+    // `nvm use system` deja el link sin versión a propósito
+    let system_active = NvmState::load(config).current_version() == Some(versions::SYSTEM_VERSION);
+    if versions::get_current_version(config).is_none() && !system_active {
+        super::use_version::activate_global(&resolved_version, config)?;
+        println!("✓ {}", t!("now_using_node").replace("{}", &resolved_version));
+    }
+
     Ok(())
 }
 
@@ -8,3 +8,9 @@
 pub mod doctor;
 pub mod self_management;
 pub mod misc;
+pub mod info;
+pub mod shims;
+pub mod exec;
+pub mod completions;
+#[cfg(not(windows))]
+pub mod env;
@@ -3,6 +3,7 @@
 
 use std::path::PathBuf;
 use std::io::{self, Write};
+use crate::config::Config;
 use crate::core::installer::*;
 use crate::core::github::{get_latest_release, get_release_by_tag, get_platform_asset_name, download_asset};
 
@@ -12,18 +13,19 @@ pub async fn install_self(
     version: Option<String>,
     dir: Option<PathBuf>,
     with_self_update: bool,
+    config: &Config,
 ) -> Result<()> {
     println!("{}", t!("install_self_start"));
 
     // Determinar versión a instalar
     let release = if let Some(ver) = version {
         if ver == "latest" {
-            get_latest_release().await?
+            get_latest_release(config).await?
         } else {
-            get_release_by_tag(&ver).await?
+            get_release_by_tag(&ver, config).await?
         }
     } else {
-        get_latest_release().await?
+        get_latest_release(config).await?
     };
 
     println!("{}", t!("install_self_version")
@@ -45,7 +47,7 @@ pub async fn install_self(
 
     // Descargar binario
     println!("\n{}", t!("downloading"));
-    download_asset(asset, &download_path).await?;
+    download_asset(asset, &download_path, config).await?;
 
     // Verificar checksum (si está disponible)
     println!("{}", t!("install_self_verifying"));
@@ -232,7 +234,7 @@ pub fn uninstall_self(
 }
 
 /// Update nvm to the latest version
-pub async fn update_self(version: Option<String>, with_self_update: bool) -> Result<()> {
+pub async fn update_self(version: Option<String>, with_self_update: bool, config: &Config) -> Result<()> {
     println!("{}", t!("update_self_start"));
 
     // Obtener ejecutable actual
@@ -243,12 +245,12 @@ pub async fn update_self(version: Option<String>, with_self_update: bool) -> Res
     // Determinar versión a instalar
     let release = if let Some(ver) = version {
         if ver == "latest" {
-            get_latest_release().await?
+            get_latest_release(config).await?
         } else {
-            get_release_by_tag(&ver).await?
+            get_release_by_tag(&ver, config).await?
         }
     } else {
-        get_latest_release().await?
+        get_latest_release(config).await?
     };
 
     println!("{}", t!("update_self_version")
@@ -270,7 +272,7 @@ pub async fn update_self(version: Option<String>, with_self_update: bool) -> Res
 
     // Descargar binario
     println!("\n{}", t!("downloading"));
-    download_asset(asset, &download_path).await?;
+    download_asset(asset, &download_path, config).await?;
 
     // Verificar checksum
     println!("{}", t!("install_self_verifying"));
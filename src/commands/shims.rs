@@ -0,0 +1,103 @@
+// Shim commands - Regenerate $NVM_HOME/shims and run a tool through a shim
+use anyhow::{anyhow, bail, Context, Result};
+use std::ffi::OsString;
+use std::path::PathBuf;
+use crate::t;
+
+use crate::config::Config;
+use crate::core::{self, detection, installer, npm, shims, versions};
+use crate::core::aliases::{Aliases, DEFAULT_ALIAS};
+use crate::core::lock::NvmLock;
+use super::list::ActiveSource;
+
+/// `nvm reshim`: regenera los shims (ej: después de `npm install -g`)
+pub fn reshim(config: &Config) -> Result<()> {
+    let _lock = NvmLock::acquire(config)?;
+    let count = regenerate_shims(config)?;
+    println!(
+        "✓ {}",
+        t!("shims_regenerated")
+            .replace("{count}", &count.to_string())
+            .replace("{dir}", &config.shims_dir().display().to_string())
+    );
+    Ok(())
+}
+
+/// Regenera los shims de todas las versiones instaladas; quien llama debe tener el lock de $NVM_HOME
+pub fn regenerate_shims(config: &Config) -> Result<usize> {
+    let version_dirs: Vec<_> = core::get_installed_versions(config)?
+        .iter()
+        .map(|version| config.versions_dir().join(version))
+        .collect();
+    let nvm_exe = installer::get_current_executable()?;
+
+    let count = shims::write_shims(&config.shims_dir(), &nvm_exe, &shims::shim_names(&version_dirs))?;
+    Ok(count)
+}
+
+/// `nvm shim <nombre> [args]`: ejecuta la herramienta de la versión que corresponde al directorio
+/// actual (archivo de versión, si no el alias `default`, si no la versión global)
+pub fn exec_shim(name: &str, args: Vec<OsString>, config: &Config) -> Result<()> {
+    let version = shim_version(config)?;
+    let mut paths: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|current| std::env::split_paths(&current).collect())
+        .unwrap_or_default();
+
+    let found = if version == versions::SYSTEM_VERSION {
+        // Node.js del sistema: el PATH sin las rutas de nvm
+        paths = detection::unmanaged_path(config);
+        paths
+            .iter()
+            .find_map(|dir| shims::find_tool(dir, name).map(|program| (dir.clone(), program)))
+    } else {
+        let bin_dir = npm::bin_dir(&config.versions_dir().join(&version));
+        shims::find_tool(&bin_dir, name).map(|program| (bin_dir, program))
+    };
+
+    let (bin_dir, program) = found.ok_or_else(|| {
+        anyhow!(t!("shim_tool_missing")
+            .replace("{tool}", name)
+            .replace("{version}", &version))
+    })?;
+
+    // El bin de la versión va primero para que `#!/usr/bin/env node` no vuelva al shim
+    paths.insert(0, bin_dir);
+
+    let version_dir = (version != versions::SYSTEM_VERSION).then(|| config.versions_dir().join(&version));
+    super::exec::run(program.as_os_str(), args, paths, version_dir.as_deref(), config)
+}
+
+fn shim_version(config: &Config) -> Result<String> {
+    shim_source(config).map(|(version, _)| version)
+}
+
+/// Versión que usan los shims y de dónde sale (también lo usa `nvm current --verbose`)
+pub(super) fn shim_source(config: &Config) -> Result<(String, ActiveSource)> {
+    let aliases = Aliases::load().unwrap_or_default();
+
+    if let Some((path, spec)) = versions::find_nvmrc_in_tree(None) {
+        let version = if spec == versions::SYSTEM_VERSION {
+            spec.clone()
+        } else {
+            super::install::resolve_installed(&spec, config).with_context(|| {
+                t!("shim_version_not_installed")
+                    .replace("{file}", &path.display().to_string())
+                    .replace("{version}", &spec)
+            })?
+        };
+        let alias_chain = aliases.chain(&spec);
+        return Ok((version, ActiveSource::VersionFile { file: path, spec, alias_chain }));
+    }
+
+    if let Some(spec) = aliases.get(DEFAULT_ALIAS) {
+        let version = super::install::resolve_installed(DEFAULT_ALIAS, config)
+            .with_context(|| format!("Alias 'default' points to {}", spec))?;
+        let alias_chain = aliases.chain(DEFAULT_ALIAS);
+        return Ok((version, ActiveSource::DefaultAlias { alias_chain }));
+    }
+
+    match versions::get_current_version(config) {
+        Some(version) => Ok((version, ActiveSource::Global { link: config.current_dir() })),
+        None => bail!(t!("shim_no_version")),
+    }
+}
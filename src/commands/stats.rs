@@ -1,12 +1,16 @@
 // Stats command - Show nvm installation summary
 use colored::Colorize;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 
 use crate::config::Config;
 use crate::core::cache::get_cache_info;
 use crate::core::aliases::Aliases;
+use crate::core::archive_cache::ArchiveCache;
 use crate::core::cache::get_cache_total_size;
+use crate::core::dedupe::file_id;
+use crate::core::manifest::InstallManifest;
 use crate::core::versions;
 
 /// Estadísticas de instalación de nvm
@@ -19,11 +23,19 @@ pub struct Stats {
     pub installed_versions_count: usize,
     pub active_version: Option<String>,
     pub total_node_size: u64,
+    /// Versiones por origen según su manifiesto (mirror, archive, url, source)
+    pub install_sources: BTreeMap<String, usize>,
+    /// Versiones instaladas sin manifiesto (anteriores a que existiera)
+    pub without_manifest: usize,
 
     pub aliases_count: usize,
     pub cache_size: u64,
     pub cache_valid: bool,
     pub cache_age: Option<u64>,
+
+    pub archive_cache_dir: PathBuf,
+    pub archive_count: usize,
+    pub archive_cache_size: u64,
 }
 
 /// Obtener estadísticas del sistema
@@ -48,13 +60,26 @@ pub async fn get_stats(config: &Config) -> anyhow::Result<Stats> {
     let versions_dir = config.versions_dir();
     let mut installed_count = 0;
     let mut total_size = 0;
+    let mut install_sources = BTreeMap::new();
+    let mut without_manifest = 0;
+    // Los archivos compartidos con hardlinks (`nvm dedupe`) se cuentan una sola vez
+    let mut seen_files = HashSet::new();
 
     if versions_dir.exists() {
         for entry in fs::read_dir(&versions_dir)?.flatten() {
             let path = entry.path();
             if path.is_dir() {
                 installed_count += 1;
-                total_size += calculate_dir_size(&path)?;
+                total_size += calculate_dir_size(&path, &mut seen_files)?;
+
+                match InstallManifest::read(&path) {
+                    Some(manifest) => {
+                        *install_sources
+                            .entry(manifest.source.as_str().to_string())
+                            .or_insert(0) += 1
+                    }
+                    None => without_manifest += 1,
+                }
             }
         }
     }
@@ -72,6 +97,12 @@ pub async fn get_stats(config: &Config) -> anyhow::Result<Stats> {
     let cache_info = get_cache_info(config)?;
     let cache_size = get_cache_total_size(config).unwrap_or(0);
 
+    // Caché de archivos descargados
+    let (archive_count, archive_cache_size) = match ArchiveCache::peek(config) {
+        Ok(cache) => (cache.len(), cache.total_size()),
+        Err(_) => (0, 0),
+    };
+
     Ok(Stats {
         nvm_version: env!("CARGO_PKG_VERSION").to_string(),
         nvm_location: nvm_dir,
@@ -79,20 +110,29 @@ pub async fn get_stats(config: &Config) -> anyhow::Result<Stats> {
         installed_versions_count: installed_count,
         active_version,
         total_node_size: total_size,
+        install_sources,
+        without_manifest,
         aliases_count,
         cache_size,
         cache_valid: cache_info.is_valid,
         cache_age: cache_info.last_updated,
+        archive_cache_dir: config.cache_dir.clone(),
+        archive_count,
+        archive_cache_size,
     })
 }
 
 /// Obtener versión activa
 fn get_active_version(config: &Config) -> Option<String> {
-    versions::get_current_version(config)
+    versions::get_active_version(config)
 }
 
 /// Calcular tamaño total de un directorio
-fn calculate_dir_size(path: &std::path::Path) -> anyhow::Result<u64> {
+/// `seen_files` evita contar dos veces un archivo enlazado con hardlinks
+fn calculate_dir_size(
+    path: &std::path::Path,
+    seen_files: &mut HashSet<(u64, u64)>,
+) -> anyhow::Result<u64> {
     let mut total = 0;
 
     for entry in fs::read_dir(path)? {
@@ -100,9 +140,12 @@ fn calculate_dir_size(path: &std::path::Path) -> anyhow::Result<u64> {
         let metadata = entry.metadata()?;
 
         if metadata.is_file() {
-            total += metadata.len();
+            let first_link = file_id(&metadata).is_none_or(|id| seen_files.insert(id));
+            if first_link {
+                total += metadata.len();
+            }
         } else if metadata.is_dir() {
-            total += calculate_dir_size(&entry.path())?;
+            total += calculate_dir_size(&entry.path(), seen_files)?;
         }
     }
 
@@ -139,17 +182,38 @@ pub fn display_stats(stats: &Stats) {
         "Total size:".bright_black(),
         format_size(stats.total_node_size)
     );
+    if !stats.install_sources.is_empty() {
+        let sources: Vec<String> = stats
+            .install_sources
+            .iter()
+            .map(|(source, count)| format!("{} {}", source, count))
+            .collect();
+        println!("   {} {}", "Sources:".bright_black(), sources.join(", "));
+    }
+    if stats.without_manifest > 0 {
+        println!(
+            "   {} {}",
+            "Without manifest:".bright_black(),
+            stats.without_manifest
+        );
+    }
 
     println!("\n{}", "🏷️  Aliases:".yellow());
     println!("   {} {}", "Defined:".bright_black(), stats.aliases_count);
 
     println!("\n{}", "💾 Cache:".yellow());
-    println!("   {} ~/.nvm/cache", "Location:".bright_black());
+    println!("   {} {}", "Location:".bright_black(), stats.archive_cache_dir.display());
     println!(
         "   {} {}",
         "Size:".bright_black(),
         format_size(stats.cache_size)
     );
+    println!(
+        "   {} {} ({})",
+        "Archives:".bright_black(),
+        stats.archive_count,
+        format_size(stats.archive_cache_size)
+    );
     if stats.cache_valid {
         println!("   {} {}", "Status:".bright_black(), "✓ Valid".green());
     } else {
@@ -172,10 +236,15 @@ pub fn display_stats_json(stats: &Stats) -> anyhow::Result<()> {
         "installed_versions": stats.installed_versions_count,
         "active_version": stats.active_version,
         "total_node_size_bytes": stats.total_node_size,
+        "install_sources": stats.install_sources,
+        "versions_without_manifest": stats.without_manifest,
         "aliases_count": stats.aliases_count,
         "cache_size_bytes": stats.cache_size,
         "cache_valid": stats.cache_valid,
         "cache_age_seconds": stats.cache_age,
+        "archive_cache_dir": stats.archive_cache_dir.to_string_lossy(),
+        "archive_count": stats.archive_count,
+        "archive_cache_size_bytes": stats.archive_cache_size,
     });
 
     println!("{}", serde_json::to_string_pretty(&json)?);
@@ -183,7 +252,7 @@ pub fn display_stats_json(stats: &Stats) -> anyhow::Result<()> {
 }
 
 /// Formato legible de tamaño
-fn format_size(bytes: u64) -> String {
+pub fn format_size(bytes: u64) -> String {
     let units = ["B", "KB", "MB", "GB"];
     let mut size = bytes as f64;
     let mut unit_idx = 0;
@@ -3,6 +3,9 @@
 
 use crate::config::Config;
 use crate::core::{self, versions, symlink, refresh_installed_cache};
+use crate::core::lock::NvmLock;
+use crate::core::state::NvmState;
+use crate::core::aliases::{Aliases, DEFAULT_ALIAS};
 
 
 pub async fn uninstall(version: &str, force: bool, config: &Config) -> Result<()> {
@@ -12,7 +15,9 @@ pub async fn uninstall(version: &str, force: bool, config: &Config) -> Result<()
     let available_versions = core::get_cached_versions(config).await?;
 
     // Resolver la versión (puede ser alias)
-    let resolved_version = versions::resolve_version(version, &available_versions)?;
+    let resolved_version = super::install::resolve_prefer_installed(version, &available_versions, config)?;
+
+    let _lock = NvmLock::acquire(config)?;
 
     // Verificar que la versión esté instalada
     let version_dir = config.versions_dir().join(&resolved_version);
@@ -22,7 +27,9 @@ pub async fn uninstall(version: &str, force: bool, config: &Config) -> Result<()
     }
 
     // Verificar si es la versión actual
-    if let Some(current_version) = versions::get_current_version(config) {
+    let current_version = versions::get_current_version(config);
+    let was_current = current_version.as_deref() == Some(resolved_version.as_str());
+    if let Some(current_version) = current_version {
         if current_version == resolved_version && !force {
             eprintln!("{}", t!("cannot_uninstall_active")
                 .replace("{version}", &resolved_version));
@@ -46,7 +53,30 @@ pub async fn uninstall(version: &str, force: bool, config: &Config) -> Result<()
     // Actualizar cache de versiones instaladas
     refresh_installed_cache(config)?;
 
+    let mut state = NvmState::load(config);
+    state.forget(&resolved_version);
+    state.save(config).context("Failed to save state.json")?;
+
+    // Quitar los shims de binarios que solo tenía esta versión
+    if config.shims {
+        if let Err(e) = super::shims::regenerate_shims(config) {
+            eprintln!("{}", t!("shims_failed").replace("{}", &e.to_string()));
+        }
+    }
+
     println!("{}", t!("version_uninstalled").replace("{version}", &resolved_version));
 
+    // Sin la versión actual, volver a la versión por defecto
+    match versions::get_default_version(config) {
+        Some(default_version) if was_current => {
+            super::use_version::activate_global(&default_version, config)?;
+            println!("✓ {}", t!("default_version_restored").replace("{}", &default_version));
+        }
+        None if Aliases::load().is_ok_and(|aliases| aliases.contains(DEFAULT_ALIAS)) => {
+            eprintln!("{}", t!("default_version_missing"));
+        }
+        _ => {}
+    }
+
     Ok(())
 }
@@ -1,46 +1,139 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use crate::t;
 
 use crate::config::Config;
-use crate::core::{self, symlink, versions};
+use crate::core::{self, detection, symlink, versions};
+use crate::core::lock::NvmLock;
+use crate::core::state::NvmState;
 
 
-pub async fn use_version(version: Option<String>, config: &Config) -> Result<()> {
+/// Código de salida de `nvm resolve` cuando la versión pedida no está instalada
+pub const RESOLVE_NOT_INSTALLED: i32 = 3;
+
+/// `nvm resolve`: versión instalada que pide el .nvmrc más cercano, sin usar la red
+/// Lo usa el hook de cambio de directorio; sin .nvmrc no imprime nada.
+/// Si no está instalada imprime la versión pedida y sale con `RESOLVE_NOT_INSTALLED`
+pub fn resolve(config: &Config) -> Result<()> {
+    let Some((_, spec)) = versions::find_nvmrc_in_tree(None) else {
+        return Ok(());
+    };
+
+    if spec == versions::SYSTEM_VERSION {
+        println!("{}", spec);
+        return Ok(());
+    }
+
+    match super::install::resolve_installed(&spec, config) {
+        Ok(version) => println!("{}", version),
+        Err(_) => {
+            println!("{}", spec);
+            std::process::exit(RESOLVE_NOT_INSTALLED);
+        }
+    }
+
+    Ok(())
+}
+
+/// Cambia la versión global ($NVM_HOME/current)
+/// Con `shell` (lo usa la función `nvm` de `nvm env`) imprime el código para cambiar
+/// solo la sesión actual; los mensajes van a stderr porque stdout se evalúa
+pub async fn use_version(version: Option<String>, shell: Option<String>, config: &Config) -> Result<()> {
+    let session = shell.is_some();
+    let say = |message: String| {
+        if session {
+            eprintln!("{}", message);
+        } else {
+            println!("{}", message);
+        }
+    };
+
     // Determinar qué versión usar
-    let version_to_use = if let Some(ver) = version {
+    let version_to_use = if version.as_deref() == Some("-") {
+        let previous = previous_version(session, config)?;
+        say(format!("Returning to previous version: {}", previous));
+        previous
+    } else if let Some(ver) = version {
         ver
     } else {
-        // Si no se especificó versión, buscar .nvmrc
+        // Si no se especificó versión, buscar .nvmrc (y si no hay, la versión por defecto)
         if let Some((nvmrc_path, nvmrc_version)) = versions::find_nvmrc_in_tree(None) {
-            println!("Found .nvmrc: {}", nvmrc_path.display());
-            println!("Using version from .nvmrc: {}", nvmrc_version);
+            say(format!("Found .nvmrc: {}", nvmrc_path.display()));
+            say(format!("Using version from .nvmrc: {}", nvmrc_version));
             nvmrc_version
+        } else if let Some(default_version) = versions::get_default_version(config) {
+            say(t!("using_default_version").replace("{}", &default_version));
+            default_version
         } else {
-            eprintln!("No version specified and no .nvmrc found");
-            eprintln!("Usage: nvm use <version>");
+            eprintln!("No version specified, no .nvmrc found and no default version set");
+            eprintln!("Usage: nvm use <version>  (or: nvm set-default <version>)");
             return Ok(());
         }
     };
 
-    println!("Switching to Node.js {}...", version_to_use);
+    if version_to_use == versions::SYSTEM_VERSION {
+        return use_system(shell, config);
+    }
 
-    // Obtener versiones disponibles para resolver aliases
-    let available_versions = core::get_cached_versions(config).await?;
+    say(format!("Switching to Node.js {}...", version_to_use));
 
     // Resolver la versión (puede ser alias)
-    let resolved_version = versions::resolve_version(&version_to_use, &available_versions)?;
+    let resolved_version = resolve_for_use(&version_to_use, config).await?;
 
     // Verificar que la versión esté instalada
     let version_dir = config.versions_dir().join(&resolved_version);
     if !version_dir.exists() {
         eprintln!("{}", t!("version_not_installed").replace("{}", &resolved_version));
         eprintln!("Run: nvm install {}", resolved_version);
+        if session {
+            bail!("{}", t!("version_not_installed").replace("{}", &resolved_version));
+        }
         return Ok(());
     }
 
-    // Crear o actualizar symlink
-    let current_link = config.current_dir();
+    if let Some(shell) = shell {
+        #[cfg(not(windows))]
+        {
+            super::env::print_use(&shell, &resolved_version, config)?;
+            eprintln!("✓ {}", t!("now_using_node_session").replace("{}", &resolved_version));
+            return Ok(());
+        }
+
+        #[cfg(windows)]
+        bail!("Session-only switching (--shell {}) is not supported on Windows", shell);
+    }
+
+    let _lock = NvmLock::acquire(config)?;
+
     println!("{}", t!("creating_symlink"));
+    activate_global(&resolved_version, config)?;
+
+    println!("\n✓ {}", t!("now_using_node").replace("{}", &resolved_version));
+
+    // Mostrar información adicional
+    if symlink::is_valid_symlink(&config.current_dir()) {
+        println!("{}", t!("symlink_created"));
+    }
+
+    Ok(())
+}
+
+/// Resuelve la versión a activar; una versión concreta ya instalada no consulta el índice
+/// remoto, así el hook de cambio de directorio no espera a la red (ni falla sin conexión)
+async fn resolve_for_use(spec: &str, config: &Config) -> Result<String> {
+    let normalized = versions::normalize_version(spec.trim());
+    if versions::node_binary_path(&config.versions_dir().join(&normalized)).is_file() {
+        return Ok(normalized);
+    }
+
+    // Obtener versiones disponibles para resolver aliases
+    let available_versions = core::get_cached_versions(config).await?;
+    super::install::resolve_prefer_installed(spec, &available_versions, config)
+}
+
+/// Apunta $NVM_HOME/current a una versión instalada y la registra en state.json
+/// (la anterior queda para `nvm use -`); quien llama debe tener el lock de $NVM_HOME
+pub fn activate_global(version: &str, config: &Config) -> Result<()> {
+    let version_dir = config.versions_dir().join(version);
 
     // En Windows, el symlink apunta a la raíz de la versión
     // En Unix, debe apuntar a la subcarpeta bin/
@@ -50,19 +143,89 @@ pub async fn use_version(version: Option<String>, config: &Config) -> Result<()>
     #[cfg(not(windows))]
     let symlink_target = &version_dir.join("bin");
 
-    symlink::create_or_update_symlink(symlink_target, &current_link)
+    symlink::create_or_update_symlink(symlink_target, &config.current_dir())
         .context("Failed to create symlink")?;
 
-    // Persistir la versión en .nvm-version para recuperación confiable
-    symlink::persist_current_version(&current_link, &resolved_version)
-        .context("Failed to persist current version")?;
+    let mut state = NvmState::load(config);
+    state.activate(version);
+    state.save(config).context("Failed to save state.json")
+}
 
-    println!("\n✓ {}", t!("now_using_node").replace("{}", &resolved_version));
+/// Versión para `nvm use -`: la anterior de la sesión (función de `nvm env`) o la global
+fn previous_version(session: bool, config: &Config) -> Result<String> {
+    let session_previous = std::env::var(versions::SESSION_PREVIOUS_VAR)
+        .ok()
+        .filter(|version| session && !version.is_empty());
 
-    // Mostrar información adicional
-    if symlink::is_valid_symlink(&current_link) {
-        println!("{}", t!("symlink_created"));
+    match session_previous.or_else(|| NvmState::load(config).previous_version().map(str::to_string)) {
+        Some(version) => Ok(version),
+        None => bail!("{}", t!("no_previous_version")),
     }
+}
+
+/// `nvm use system`: sin versión de nvm activa, el Node.js del sistema queda primero en el PATH
+/// Global: se elimina el link `current`; con `shell`, solo se quitan las rutas de nvm de la sesión
+fn use_system(shell: Option<String>, config: &Config) -> Result<()> {
+    if let Some(shell) = shell {
+        #[cfg(not(windows))]
+        {
+            super::env::print_use(&shell, versions::SYSTEM_VERSION, config)?;
+            print_system_node(true, config);
+            return Ok(());
+        }
+
+        #[cfg(windows)]
+        bail!("Session-only switching (--shell {}) is not supported on Windows", shell);
+    }
+
+    let _lock = NvmLock::acquire(config)?;
+    symlink::remove_symlink(&config.current_dir()).context("Failed to remove current symlink")?;
+
+    let mut state = NvmState::load(config);
+    state.activate(versions::SYSTEM_VERSION);
+    state.save(config).context("Failed to save state.json")?;
+    print_system_node(false, config);
 
     Ok(())
 }
+
+fn print_system_node(session: bool, config: &Config) {
+    let message = match detection::find_system_node(config) {
+        Some(info) => format!(
+            "✓ {}",
+            t!("now_using_system_node")
+                .replace("{version}", &info.version)
+                .replace("{path}", &info.path.display().to_string())
+        ),
+        None => t!("system_node_not_found"),
+    };
+
+    if session {
+        eprintln!("{}", message);
+    } else {
+        println!("{}", message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_installed_version_resolves_offline() {
+        let temp = TempDir::new().unwrap();
+        let mut config = Config::for_tests(temp.path());
+        // Un mirror inalcanzable: cualquier consulta al índice remoto falla
+        config.node_mirror = "http://127.0.0.1:1".to_string();
+
+        let version_dir = config.versions_dir().join("v20.11.0");
+        let binary = versions::node_binary_path(&version_dir);
+        std::fs::create_dir_all(binary.parent().unwrap()).unwrap();
+        std::fs::write(&binary, "").unwrap();
+
+        assert_eq!(resolve_for_use("20.11.0", &config).await.unwrap(), "v20.11.0");
+        assert_eq!(resolve_for_use("v20.11.0", &config).await.unwrap(), "v20.11.0");
+        assert!(resolve_for_use("v18.19.0", &config).await.is_err());
+    }
+}
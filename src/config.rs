@@ -1,5 +1,9 @@
-use anyhow::Result;
-use std::path::PathBuf;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::utils::auth::{self, MirrorCredentials};
 
 // Environment variables homologation
 pub const NVM_HOME_VAR: &str = "NVM_HOME";
@@ -8,6 +12,191 @@
 #[allow(dead_code)] // Reserved for future phases
 pub const NVM_NODE_VAR: &str = "NVM_NODE";
 pub const NODE_MIRROR_VAR: &str = "NODE_MIRROR";
+pub const NVM_CA_BUNDLE_VAR: &str = "NVM_CA_BUNDLE";
+pub const NVM_HTTP_TIMEOUT_VAR: &str = "NVM_HTTP_TIMEOUT";
+pub const NVM_CONNECT_TIMEOUT_VAR: &str = "NVM_CONNECT_TIMEOUT";
+pub const NVM_MIRROR_TOKEN_VAR: &str = "NVM_MIRROR_TOKEN";
+pub const NVM_MIRROR_USERNAME_VAR: &str = "NVM_MIRROR_USERNAME";
+pub const NVM_MIRROR_PASSWORD_VAR: &str = "NVM_MIRROR_PASSWORD";
+pub const NVM_CACHE_DIR_VAR: &str = "NVM_CACHE_DIR";
+pub const NVM_CACHE_MAX_SIZE_VAR: &str = "NVM_CACHE_MAX_SIZE";
+pub const NVM_LOCK_TIMEOUT_VAR: &str = "NVM_LOCK_TIMEOUT";
+pub const NVM_COREPACK_ENABLE_VAR: &str = "NVM_COREPACK_ENABLE";
+pub const NVM_COREPACK_PREPARE_VAR: &str = "NVM_COREPACK_PREPARE";
+pub const NVM_DEDUPE_VAR: &str = "NVM_DEDUPE";
+pub const NVM_USE_ON_CD_VAR: &str = "NVM_USE_ON_CD";
+pub const NVM_INSTALL_ON_CD_VAR: &str = "NVM_INSTALL_ON_CD";
+pub const NVM_SHIMS_VAR: &str = "NVM_SHIMS";
+pub const NVM_COMPLETIONS_VAR: &str = "NVM_COMPLETIONS";
+pub const NVM_NPM_PREFIX_ISOLATION_VAR: &str = "NVM_NPM_PREFIX_ISOLATION";
+pub const NVM_NPM_CACHE_VAR: &str = "NVM_NPM_CACHE";
+
+const CONFIG_FILE_NAME: &str = "config.json";
+const DEFAULT_NODE_MIRROR: &str = "https://nodejs.org/dist";
+const DEFAULT_HTTP_TIMEOUT_SECS: u64 = 300;
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_CACHE_MAX_SIZE_MB: u64 = 1024;
+const DEFAULT_LOCK_TIMEOUT_SECS: u64 = 600;
+
+/// Opciones opcionales leídas desde $NVM_HOME/config.json
+/// Las variables de entorno tienen prioridad sobre este archivo
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FileConfig {
+    node_mirror: Option<String>,
+    proxy: Option<String>,
+    no_proxy: Option<String>,
+    ca_bundle: Option<PathBuf>,
+    http_timeout_secs: Option<u64>,
+    connect_timeout_secs: Option<u64>,
+    /// Credenciales por host del mirror: { "host": { "token": "..." } }
+    mirror_credentials: HashMap<String, MirrorCredentials>,
+    cache_dir: Option<PathBuf>,
+    cache_max_size_mb: Option<u64>,
+    lock_timeout_secs: Option<u64>,
+    /// Ejecutar `corepack enable` tras cada instalación
+    corepack_enable: Option<bool>,
+    /// Preparar el packageManager del package.json más cercano tras instalar
+    corepack_prepare: Option<bool>,
+    /// Deduplicar con hardlinks después de cada instalación
+    dedupe_on_install: Option<bool>,
+    /// Cambiar de versión al entrar en un directorio con .nvmrc (hook del shell)
+    use_on_cd: Option<bool>,
+    /// Instalar desde el hook la versión del .nvmrc si falta
+    install_on_cd: Option<bool>,
+    /// Usar $NVM_HOME/shims (versión resuelta en cada ejecución) en lugar del link global
+    shims: Option<bool>,
+    /// Cargar las completions de `nvm completions` desde el bloque del shell
+    completions: Option<bool>,
+    /// Fijar `npm_config_prefix` en la versión al activarla (paquetes globales por versión)
+    npm_prefix_isolation: Option<bool>,
+    /// Caché de npm al activar una versión: "default", "shared" o "per-version"
+    npm_cache: Option<NpmCacheMode>,
+}
+
+/// Caché de npm que fija nvm al activar una versión (`npm_config_cache`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NpmCacheMode {
+    /// La caché de npm del usuario (no se toca `npm_config_cache`)
+    #[default]
+    Default,
+    /// $NVM_HOME/npm-cache, compartida por todas las versiones
+    Shared,
+    /// Una caché dentro de cada versión, que se elimina al desinstalarla
+    PerVersion,
+}
+
+impl NpmCacheMode {
+    fn parse(var: &str, value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "default" => Ok(NpmCacheMode::Default),
+            "shared" => Ok(NpmCacheMode::Shared),
+            "per-version" => Ok(NpmCacheMode::PerVersion),
+            _ => anyhow::bail!(
+                "Invalid value for {}: '{}' (expected default, shared or per-version)",
+                var,
+                value
+            ),
+        }
+    }
+}
+
+impl FileConfig {
+    fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse config file: {}", path.display()))
+    }
+}
+
+/// Configuración de red compartida por todas las peticiones HTTP
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    /// Proxy explícito (HTTPS_PROXY, HTTP_PROXY, ALL_PROXY o config.json)
+    pub proxy: Option<String>,
+    /// Lista de hosts que no pasan por el proxy (NO_PROXY)
+    pub no_proxy: Option<String>,
+    /// Bundle PEM con certificados raíz adicionales (proxies con inspección TLS)
+    pub ca_bundle: Option<PathBuf>,
+    pub timeout_secs: u64,
+    pub connect_timeout_secs: u64,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            proxy: None,
+            no_proxy: None,
+            ca_bundle: None,
+            timeout_secs: DEFAULT_HTTP_TIMEOUT_SECS,
+            connect_timeout_secs: DEFAULT_CONNECT_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl HttpConfig {
+    fn from_sources(file: &FileConfig) -> Result<Self> {
+        let proxy = first_env(&["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy", "HTTP_PROXY", "http_proxy"])
+            .or_else(|| file.proxy.clone());
+        let no_proxy = first_env(&["NO_PROXY", "no_proxy"]).or_else(|| file.no_proxy.clone());
+
+        // NODE_EXTRA_CA_CERTS es la convención de Node.js para el mismo propósito
+        let ca_bundle = first_env(&[NVM_CA_BUNDLE_VAR, "NODE_EXTRA_CA_CERTS"])
+            .map(PathBuf::from)
+            .or_else(|| file.ca_bundle.clone());
+
+        let timeout_secs = match first_env(&[NVM_HTTP_TIMEOUT_VAR]) {
+            Some(value) => parse_secs(NVM_HTTP_TIMEOUT_VAR, &value)?,
+            None => file.http_timeout_secs.unwrap_or(DEFAULT_HTTP_TIMEOUT_SECS),
+        };
+        let connect_timeout_secs = match first_env(&[NVM_CONNECT_TIMEOUT_VAR]) {
+            Some(value) => parse_secs(NVM_CONNECT_TIMEOUT_VAR, &value)?,
+            None => file.connect_timeout_secs.unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS),
+        };
+
+        Ok(HttpConfig {
+            proxy,
+            no_proxy,
+            ca_bundle,
+            timeout_secs,
+            connect_timeout_secs,
+        })
+    }
+}
+
+/// Devuelve el valor de la primera variable de entorno definida y no vacía
+fn first_env(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.trim().is_empty())
+}
+
+fn parse_bool(var: &str, value: &str) -> Result<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => anyhow::bail!("Invalid value for {}: '{}' (expected true/false)", var, value),
+    }
+}
+
+fn parse_secs(var: &str, value: &str) -> Result<u64> {
+    value
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid value for {}: '{}' (expected seconds)", var, value))
+}
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -16,6 +205,33 @@ pub struct Config {
     pub arch: String,
     pub cache_duration_minutes: u64,
     pub installed_cache_duration_minutes: u64,
+    pub http: HttpConfig,
+    /// Credenciales por host (config.json, NODE_MIRROR con user:pass@, variables NVM_MIRROR_*)
+    pub credentials: HashMap<String, MirrorCredentials>,
+    /// Directorio de archivos descargados (puede compartirse entre varios NVM_HOME)
+    pub cache_dir: PathBuf,
+    /// Tamaño máximo del caché de archivos en MB (0 = no conservar archivos)
+    pub cache_max_size_mb: u64,
+    /// Tiempo máximo de espera por el lock de $NVM_HOME
+    pub lock_timeout_secs: u64,
+    /// Valor por defecto de `nvm install --corepack`
+    pub corepack_enable: bool,
+    /// Valor por defecto de `nvm install --corepack-prepare`
+    pub corepack_prepare: bool,
+    /// Valor por defecto de `nvm install --dedupe`
+    pub dedupe_on_install: bool,
+    /// Incluir el hook de cambio de directorio en el bloque del shell
+    pub use_on_cd: bool,
+    /// El hook instala la versión del .nvmrc si no está instalada
+    pub install_on_cd: bool,
+    /// Modo shims: regenerar $NVM_HOME/shims tras instalar y ponerlo en el PATH del shell
+    pub shims: bool,
+    /// Cargar las completions desde el bloque del shell
+    pub completions: bool,
+    /// Fijar `npm_config_prefix` en la versión activada
+    pub npm_prefix_isolation: bool,
+    /// Caché de npm de las versiones activadas
+    pub npm_cache: NpmCacheMode,
 }
 
 impl Config {
@@ -28,17 +244,130 @@ pub fn new() -> Result<Self> {
         }
 
         let arch = Self::detect_arch();
+        let file_config = FileConfig::load(&nvm_dir.join(CONFIG_FILE_NAME))?;
+        let http = HttpConfig::from_sources(&file_config)?;
+
+        let raw_mirror = first_env(&[NODE_MIRROR_VAR])
+            .or(file_config.node_mirror)
+            .unwrap_or_else(|| DEFAULT_NODE_MIRROR.to_string());
+
+        // Las credenciales embebidas en la URL nunca se guardan en node_mirror
+        let (node_mirror, url_credentials) = auth::split_url_credentials(&raw_mirror);
+        let credentials = Self::collect_credentials(
+            &node_mirror,
+            file_config.mirror_credentials,
+            url_credentials,
+        );
+
+        let cache_dir = first_env(&[NVM_CACHE_DIR_VAR])
+            .map(PathBuf::from)
+            .or(file_config.cache_dir)
+            .unwrap_or_else(|| nvm_dir.join("cache"));
+        let cache_max_size_mb = match first_env(&[NVM_CACHE_MAX_SIZE_VAR]) {
+            Some(value) => value
+                .trim()
+                .parse()
+                .with_context(|| format!("Invalid value for {}: '{}' (expected MB)", NVM_CACHE_MAX_SIZE_VAR, value))?,
+            None => file_config.cache_max_size_mb.unwrap_or(DEFAULT_CACHE_MAX_SIZE_MB),
+        };
+        let lock_timeout_secs = match first_env(&[NVM_LOCK_TIMEOUT_VAR]) {
+            Some(value) => parse_secs(NVM_LOCK_TIMEOUT_VAR, &value)?,
+            None => file_config.lock_timeout_secs.unwrap_or(DEFAULT_LOCK_TIMEOUT_SECS),
+        };
+        let corepack_enable = match first_env(&[NVM_COREPACK_ENABLE_VAR]) {
+            Some(value) => parse_bool(NVM_COREPACK_ENABLE_VAR, &value)?,
+            None => file_config.corepack_enable.unwrap_or(false),
+        };
+        let corepack_prepare = match first_env(&[NVM_COREPACK_PREPARE_VAR]) {
+            Some(value) => parse_bool(NVM_COREPACK_PREPARE_VAR, &value)?,
+            None => file_config.corepack_prepare.unwrap_or(false),
+        };
+        let dedupe_on_install = match first_env(&[NVM_DEDUPE_VAR]) {
+            Some(value) => parse_bool(NVM_DEDUPE_VAR, &value)?,
+            None => file_config.dedupe_on_install.unwrap_or(false),
+        };
+        let use_on_cd = match first_env(&[NVM_USE_ON_CD_VAR]) {
+            Some(value) => parse_bool(NVM_USE_ON_CD_VAR, &value)?,
+            None => file_config.use_on_cd.unwrap_or(false),
+        };
+        let install_on_cd = match first_env(&[NVM_INSTALL_ON_CD_VAR]) {
+            Some(value) => parse_bool(NVM_INSTALL_ON_CD_VAR, &value)?,
+            None => file_config.install_on_cd.unwrap_or(false),
+        };
+        let shims = match first_env(&[NVM_SHIMS_VAR]) {
+            Some(value) => parse_bool(NVM_SHIMS_VAR, &value)?,
+            None => file_config.shims.unwrap_or(false),
+        };
+        let completions = match first_env(&[NVM_COMPLETIONS_VAR]) {
+            Some(value) => parse_bool(NVM_COMPLETIONS_VAR, &value)?,
+            None => file_config.completions.unwrap_or(false),
+        };
+        let npm_prefix_isolation = match first_env(&[NVM_NPM_PREFIX_ISOLATION_VAR]) {
+            Some(value) => parse_bool(NVM_NPM_PREFIX_ISOLATION_VAR, &value)?,
+            None => file_config.npm_prefix_isolation.unwrap_or(true),
+        };
+        let npm_cache = match first_env(&[NVM_NPM_CACHE_VAR]) {
+            Some(value) => NpmCacheMode::parse(NVM_NPM_CACHE_VAR, &value)?,
+            None => file_config.npm_cache.unwrap_or_default(),
+        };
 
         Ok(Config {
             nvm_dir,
-            node_mirror: std::env::var(NODE_MIRROR_VAR)
-                .unwrap_or_else(|_| "https://nodejs.org/dist".to_string()),
+            node_mirror,
             arch,
             cache_duration_minutes: 24 * 60,  // 24 hours
             installed_cache_duration_minutes: 5,
+            http,
+            credentials,
+            cache_dir,
+            cache_max_size_mb,
+            lock_timeout_secs,
+            corepack_enable,
+            corepack_prepare,
+            dedupe_on_install,
+            use_on_cd,
+            install_on_cd,
+            shims,
+            completions,
+            npm_prefix_isolation,
+            npm_cache,
         })
     }
 
+    /// Combina las fuentes de credenciales (prioridad: env > URL > config.json)
+    /// netrc se consulta aparte, al hacer cada petición
+    fn collect_credentials(
+        node_mirror: &str,
+        file_credentials: HashMap<String, MirrorCredentials>,
+        url_credentials: Option<(String, MirrorCredentials)>,
+    ) -> HashMap<String, MirrorCredentials> {
+        let mut credentials: HashMap<String, MirrorCredentials> = file_credentials
+            .into_iter()
+            .map(|(host, creds)| (host.to_lowercase(), creds))
+            .collect();
+
+        if let Some((host, creds)) = url_credentials {
+            credentials.insert(host, creds);
+        }
+
+        let env_credentials = if let Some(token) = first_env(&[NVM_MIRROR_TOKEN_VAR]) {
+            Some(MirrorCredentials::Bearer { token })
+        } else {
+            first_env(&[NVM_MIRROR_USERNAME_VAR]).map(|username| MirrorCredentials::Basic {
+                username,
+                password: first_env(&[NVM_MIRROR_PASSWORD_VAR]).unwrap_or_default(),
+            })
+        };
+
+        if let Some(creds) = env_credentials {
+            if let Some(host) = reqwest::Url::parse(node_mirror).ok().as_ref().and_then(auth::host_key) {
+                credentials.insert(host, creds);
+            }
+        }
+
+        credentials
+    }
+
     fn get_nvm_dir() -> Result<PathBuf> {
         // Check NVM_HOME environment variable first
         if let Ok(nvm_home) = std::env::var(NVM_HOME_VAR) {
@@ -82,6 +411,26 @@ pub fn current_dir(&self) -> PathBuf {
         self.nvm_node_dir()
     }
 
+    /// Shims de node, npm, npx, corepack y los binarios globales ($NVM_HOME/shims)
+    pub fn shims_dir(&self) -> PathBuf {
+        self.nvm_dir.join("shims")
+    }
+
+    /// Paquetes globales a instalar tras cada `nvm install` (formato de nvm-sh)
+    pub fn default_packages_file(&self) -> PathBuf {
+        self.nvm_dir.join("default-packages")
+    }
+
+    /// Caché de npm compartida (`npm_cache: "shared"`)
+    pub fn npm_cache_dir(&self) -> PathBuf {
+        self.nvm_dir.join("npm-cache")
+    }
+
+    /// Logs de compilación de `nvm install --source` ($NVM_HOME/build-logs/<versión>)
+    pub fn build_logs_dir(&self) -> PathBuf {
+        self.nvm_dir.join("build-logs")
+    }
+
     pub fn cache_file(&self) -> PathBuf {
         self.nvm_dir.join(".version_cache.json")
     }
@@ -91,8 +440,125 @@ pub fn installed_cache_file(&self) -> PathBuf {
     }
 }
 
+#[cfg(test)]
+impl Config {
+    /// Configuración aislada para tests: no lee variables de entorno ni config.json
+    pub fn for_tests(nvm_dir: &Path) -> Self {
+        Config {
+            nvm_dir: nvm_dir.to_path_buf(),
+            node_mirror: DEFAULT_NODE_MIRROR.to_string(),
+            arch: Self::detect_arch(),
+            cache_duration_minutes: 15,
+            installed_cache_duration_minutes: 5,
+            http: HttpConfig::default(),
+            credentials: HashMap::new(),
+            cache_dir: nvm_dir.join("cache"),
+            cache_max_size_mb: DEFAULT_CACHE_MAX_SIZE_MB,
+            lock_timeout_secs: DEFAULT_LOCK_TIMEOUT_SECS,
+            corepack_enable: false,
+            corepack_prepare: false,
+            dedupe_on_install: false,
+            use_on_cd: false,
+            install_on_cd: false,
+            shims: false,
+            completions: false,
+            npm_prefix_isolation: true,
+            npm_cache: NpmCacheMode::Default,
+        }
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self::new().expect("Failed to create default config")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_file_config_missing_is_default() {
+        let temp = TempDir::new().unwrap();
+        let file = FileConfig::load(&temp.path().join(CONFIG_FILE_NAME)).unwrap();
+        assert!(file.proxy.is_none());
+        assert!(file.http_timeout_secs.is_none());
+    }
+
+    #[test]
+    fn test_file_config_parses_http_settings() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(CONFIG_FILE_NAME);
+        std::fs::write(
+            &path,
+            r#"{"proxy": "http://proxy.local:3128", "ca_bundle": "/etc/ssl/corp.pem", "http_timeout_secs": 60}"#,
+        )
+        .unwrap();
+
+        let file = FileConfig::load(&path).unwrap();
+        assert_eq!(file.proxy.as_deref(), Some("http://proxy.local:3128"));
+        assert_eq!(file.ca_bundle, Some(PathBuf::from("/etc/ssl/corp.pem")));
+        assert_eq!(file.http_timeout_secs, Some(60));
+        assert!(file.connect_timeout_secs.is_none());
+    }
+
+    #[test]
+    fn test_file_config_parses_mirror_credentials() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(CONFIG_FILE_NAME);
+        std::fs::write(
+            &path,
+            r#"{"mirror_credentials": {
+                "Artifactory.corp": {"username": "ci", "password": "pw"},
+                "cdn.corp": {"token": "abc"}
+            }}"#,
+        )
+        .unwrap();
+
+        let file = FileConfig::load(&path).unwrap();
+        let credentials = Config::collect_credentials(
+            "https://artifactory.corp/nodejs",
+            file.mirror_credentials,
+            None,
+        );
+
+        assert!(matches!(
+            credentials.get("artifactory.corp"),
+            Some(MirrorCredentials::Basic { username, .. }) if username == "ci"
+        ));
+        assert!(matches!(
+            credentials.get("cdn.corp"),
+            Some(MirrorCredentials::Bearer { token }) if token == "abc"
+        ));
+    }
+
+    #[test]
+    fn test_parse_secs_rejects_garbage() {
+        assert_eq!(parse_secs(NVM_HTTP_TIMEOUT_VAR, " 45 ").unwrap(), 45);
+        assert!(parse_secs(NVM_HTTP_TIMEOUT_VAR, "soon").is_err());
+    }
+
+    #[test]
+    fn test_parse_bool() {
+        assert!(parse_bool(NVM_COREPACK_ENABLE_VAR, "1").unwrap());
+        assert!(parse_bool(NVM_COREPACK_ENABLE_VAR, " TRUE ").unwrap());
+        assert!(!parse_bool(NVM_COREPACK_ENABLE_VAR, "off").unwrap());
+        assert!(parse_bool(NVM_COREPACK_ENABLE_VAR, "maybe").is_err());
+    }
+
+    #[test]
+    fn test_npm_cache_mode() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(CONFIG_FILE_NAME);
+        std::fs::write(&path, r#"{"npm_cache": "per-version", "npm_prefix_isolation": false}"#).unwrap();
+
+        let file = FileConfig::load(&path).unwrap();
+        assert_eq!(file.npm_cache, Some(NpmCacheMode::PerVersion));
+        assert_eq!(file.npm_prefix_isolation, Some(false));
+
+        assert_eq!(NpmCacheMode::parse(NVM_NPM_CACHE_VAR, " Shared ").unwrap(), NpmCacheMode::Shared);
+        assert!(NpmCacheMode::parse(NVM_NPM_CACHE_VAR, "tmp").is_err());
+    }
+}
@@ -3,13 +3,97 @@
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Alias con la versión por defecto (`nvm set-default`)
+pub const DEFAULT_ALIAS: &str = "default";
 
 /// Estructura para almacenar aliases de versiones de Node.js
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Aliases {
-    /// Mapa de nombre de alias -> versión
+    /// Mapa de nombre de alias -> entrada
     #[serde(flatten)]
-    pub aliases: HashMap<String, String>,
+    pub aliases: HashMap<String, AliasEntry>,
+}
+
+/// Alias: una versión fija, o un spec (`lts/*`, `^20`) que se vuelve a resolver en cada uso
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "StoredAlias", into = "StoredAlias")]
+pub struct AliasEntry {
+    /// Versión concreta ("v20.11.0"), otro alias, o el spec que sigue si `tracking`
+    pub target: String,
+    /// Se resuelve al usarlo (versiones instaladas o remotas) en lugar de quedar fijo
+    pub tracking: bool,
+    pub description: Option<String>,
+    /// Segundos desde UNIX_EPOCH; `None` en aliases creados antes de guardar metadatos
+    pub created_at: Option<u64>,
+}
+
+impl AliasEntry {
+    /// Alias fijo a `target`, creado ahora
+    pub fn new(target: String) -> Self {
+        AliasEntry {
+            target,
+            tracking: false,
+            description: None,
+            created_at: Some(
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+            ),
+        }
+    }
+}
+
+/// Formato en aliases.json: el anterior (`"nombre": "v20.11.0"`) se sigue leyendo y se conserva
+/// para los aliases sin metadatos
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum StoredAlias {
+    Version(String),
+    Entry {
+        target: String,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        tracking: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        created_at: Option<u64>,
+    },
+}
+
+impl From<StoredAlias> for AliasEntry {
+    fn from(stored: StoredAlias) -> Self {
+        match stored {
+            StoredAlias::Version(target) => AliasEntry {
+                target,
+                tracking: false,
+                description: None,
+                created_at: None,
+            },
+            StoredAlias::Entry { target, tracking, description, created_at } => AliasEntry {
+                target,
+                tracking,
+                description,
+                created_at,
+            },
+        }
+    }
+}
+
+impl From<AliasEntry> for StoredAlias {
+    fn from(entry: AliasEntry) -> Self {
+        if !entry.tracking && entry.description.is_none() && entry.created_at.is_none() {
+            return StoredAlias::Version(entry.target);
+        }
+        StoredAlias::Entry {
+            target: entry.target,
+            tracking: entry.tracking,
+            description: entry.description,
+            created_at: entry.created_at,
+        }
+    }
 }
 
 impl Aliases {
@@ -82,19 +166,24 @@ pub fn save(&self) -> Result<()> {
         Ok(())
     }
 
-    /// Añade o actualiza un alias
+    /// Añade o actualiza un alias fijo a una versión
     pub fn set(&mut self, name: String, version: String) {
-        self.aliases.insert(name, version);
+        self.aliases.insert(name, AliasEntry::new(version));
+    }
+
+    /// Añade o actualiza un alias con todos sus datos
+    pub fn insert(&mut self, name: String, entry: AliasEntry) {
+        self.aliases.insert(name, entry);
     }
 
-    /// Elimina un alias
+    /// Elimina un alias y devuelve su versión o spec
     pub fn remove(&mut self, name: &str) -> Option<String> {
-        self.aliases.remove(name)
+        self.aliases.remove(name).map(|entry| entry.target)
     }
 
-    /// Obtiene la versión asociada a un alias
+    /// Obtiene la versión (o el spec, si sigue uno) asociada a un alias
     pub fn get(&self, name: &str) -> Option<&String> {
-        self.aliases.get(name)
+        self.aliases.get(name).map(|entry| &entry.target)
     }
 
     /// Verifica si existe un alias
@@ -103,7 +192,7 @@ pub fn contains(&self, name: &str) -> bool {
     }
 
     /// Obtiene todos los aliases ordenados por nombre
-    pub fn list(&self) -> Vec<(&String, &String)> {
+    pub fn list(&self) -> Vec<(&String, &AliasEntry)> {
         let mut aliases: Vec<_> = self.aliases.iter().collect();
         aliases.sort_by(|a, b| a.0.cmp(b.0));
         aliases
@@ -128,26 +217,17 @@ pub fn is_valid_alias_name(name: &str) -> bool {
         name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '/')
     }
 
-    /// Resuelve un alias a una versión concreta
-    /// Si el input ya es una versión, la retorna sin cambios
-    /// Si es un alias, retorna la versión asociada
-    pub fn resolve(&self, name_or_version: &str) -> Option<String> {
-        // Si empieza con 'v' y tiene dígitos, probablemente es una versión
-        if name_or_version.starts_with('v') {
-            if let Some(second_char) = name_or_version.chars().nth(1) {
-                if second_char.is_ascii_digit() {
-                    return Some(name_or_version.to_string());
-                }
+    /// Cadena de aliases que sigue `name` hasta un valor que no es alias
+    /// (ej: `["default", "stable", "v20.11.0"]`); `[name]` si no es un alias
+    pub fn chain(&self, name: &str) -> Vec<String> {
+        let mut chain = vec![name.to_string()];
+        while let Some(next) = self.get(chain.last().unwrap()) {
+            if chain.contains(next) {
+                break;
             }
+            chain.push(next.clone());
         }
-
-        // Intentar resolver como alias
-        if let Some(version) = self.get(name_or_version) {
-            return Some(version.clone());
-        }
-
-        // Si no es alias ni versión, retornar None
-        None
+        chain
     }
 }
 
@@ -228,21 +308,36 @@ fn test_valid_alias_names() {
     }
 
     #[test]
-    fn test_resolve() {
+    fn test_load_legacy_format() {
+        let aliases: Aliases =
+            serde_json::from_str(r#"{"default": "v20.10.0", "stable": {"target": "lts/*", "tracking": true}}"#)
+                .unwrap();
+
+        let default = &aliases.aliases["default"];
+        assert_eq!(default.target, "v20.10.0");
+        assert!(!default.tracking);
+        assert_eq!(default.created_at, None);
+
+        let stable = &aliases.aliases["stable"];
+        assert_eq!(stable.target, "lts/*");
+        assert!(stable.tracking);
+
+        // Los aliases sin metadatos se guardan en el formato anterior
+        let json: serde_json::Value = serde_json::to_value(&aliases).unwrap();
+        assert_eq!(json["default"], "v20.10.0");
+        assert_eq!(json["stable"], serde_json::json!({"target": "lts/*", "tracking": true}));
+    }
+
+    #[test]
+    fn test_chain() {
         let mut aliases = Aliases::new();
-        aliases.set("default".to_string(), "v20.10.0".to_string());
+        aliases.set("default".to_string(), "stable".to_string());
         aliases.set("stable".to_string(), "v22.0.0".to_string());
-        
-        // Resolver alias
-        assert_eq!(aliases.resolve("default"), Some("v20.10.0".to_string()));
-        assert_eq!(aliases.resolve("stable"), Some("v22.0.0".to_string()));
-        
-        // Versiones directas
-        assert_eq!(aliases.resolve("v20.10.0"), Some("v20.10.0".to_string()));
-        assert_eq!(aliases.resolve("v22.0.0"), Some("v22.0.0".to_string()));
-        
-        // No existe
-        assert_eq!(aliases.resolve("nonexistent"), None);
+        aliases.set("loop".to_string(), "loop".to_string());
+
+        assert_eq!(aliases.chain("default"), ["default", "stable", "v22.0.0"]);
+        assert_eq!(aliases.chain("20"), ["20"]);
+        assert_eq!(aliases.chain("loop"), ["loop"]);
     }
 
     #[test]
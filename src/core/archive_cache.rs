@@ -0,0 +1,248 @@
+// Persistent cache of verified Node.js archives with LRU eviction
+use crate::config::Config;
+use crate::core::lock::NvmLock;
+use crate::error::{with_context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const INDEX_FILE: &str = ".archives.json";
+
+/// Entrada del índice: checksum verificado, tamaño y último uso
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub sha256: String,
+    pub size: u64,
+    /// Segundos desde UNIX_EPOCH
+    pub last_used: u64,
+}
+
+/// Caché de archivos descargados en `Config::cache_dir`
+/// Solo se registran archivos cuyo checksum fue verificado
+#[derive(Debug)]
+pub struct ArchiveCache {
+    dir: PathBuf,
+    max_size_bytes: u64,
+    entries: BTreeMap<String, CacheEntry>,
+    /// El directorio puede compartirse entre varios $NVM_HOME: el lock de $NVM_HOME no alcanza
+    _lock: Option<NvmLock>,
+}
+
+impl ArchiveCache {
+    /// Abre el caché, descartando entradas cuyo archivo ya no existe
+    /// Toma el lock del directorio del caché hasta que se libera el `ArchiveCache`
+    pub fn open(config: &Config) -> Result<Self> {
+        Self::create_dir(&config.cache_dir)?;
+        let lock = NvmLock::acquire_cache(config)?;
+        let mut cache = Self::open_dir(&config.cache_dir, max_size_bytes(config))?;
+        cache._lock = Some(lock);
+        Ok(cache)
+    }
+
+    /// Lee el índice sin tomar el lock, para mostrarlo (no debe modificarse)
+    pub fn peek(config: &Config) -> Result<Self> {
+        Self::open_dir(&config.cache_dir, max_size_bytes(config))
+    }
+
+    fn create_dir(dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir).map_err(|e| {
+            with_context(&format!("Failed to create cache directory: {}", dir.display()), e)
+        })
+    }
+
+    fn open_dir(dir: &Path, max_size_bytes: u64) -> Result<Self> {
+        Self::create_dir(dir)?;
+
+        let index_path = dir.join(INDEX_FILE);
+        let mut entries: BTreeMap<String, CacheEntry> = if index_path.exists() {
+            let content = fs::read_to_string(&index_path)?;
+            // Un índice corrupto no debe impedir instalar: se reconstruye
+            serde_json::from_str(&content).unwrap_or_default()
+        } else {
+            BTreeMap::new()
+        };
+
+        entries.retain(|name, _| dir.join(name).is_file());
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            max_size_bytes,
+            entries,
+            _lock: None,
+        })
+    }
+
+    /// Ruta donde se guarda (o guardaría) un archivo
+    pub fn path_for(&self, filename: &str) -> PathBuf {
+        self.dir.join(filename)
+    }
+
+    /// Checksum verificado registrado para un archivo
+    pub fn checksum_of(&self, filename: &str) -> Option<&str> {
+        self.entries.get(filename).map(|e| e.sha256.as_str())
+    }
+
+    pub fn contains(&self, filename: &str) -> bool {
+        self.entries.contains_key(filename)
+    }
+
+    /// Registra un archivo verificado y lo marca como recién usado
+    pub fn record(&mut self, filename: &str, sha256: &str) -> Result<()> {
+        self.record_at(filename, sha256, now_secs())
+    }
+
+    fn record_at(&mut self, filename: &str, sha256: &str, last_used: u64) -> Result<()> {
+        let size = fs::metadata(self.path_for(filename))?.len();
+        self.entries.insert(
+            filename.to_string(),
+            CacheEntry {
+                sha256: sha256.to_lowercase(),
+                size,
+                last_used,
+            },
+        );
+        self.save()
+    }
+
+    /// Elimina un archivo del caché (y del disco)
+    pub fn remove(&mut self, filename: &str) -> Result<()> {
+        self.entries.remove(filename);
+        let path = self.path_for(filename);
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| {
+                with_context(&format!("Failed to remove cached archive: {}", path.display()), e)
+            })?;
+        }
+        self.save()
+    }
+
+    /// Tamaño total de los archivos registrados
+    pub fn total_size(&self) -> u64 {
+        self.entries.values().map(|e| e.size).sum()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Elimina los archivos menos usados recientemente hasta respetar el tamaño máximo
+    /// Devuelve los nombres de los archivos eliminados
+    pub fn evict(&mut self) -> Result<Vec<String>> {
+        let mut by_age: Vec<(String, u64)> = self
+            .entries
+            .iter()
+            .map(|(name, entry)| (name.clone(), entry.last_used))
+            .collect();
+        by_age.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+
+        let mut evicted = Vec::new();
+        for (name, _) in by_age {
+            if self.total_size() <= self.max_size_bytes {
+                break;
+            }
+            self.remove(&name)?;
+            evicted.push(name);
+        }
+
+        Ok(evicted)
+    }
+
+    fn save(&self) -> Result<()> {
+        let content = serde_json::to_string_pretty(&self.entries)?;
+        fs::write(self.dir.join(INDEX_FILE), content)?;
+        Ok(())
+    }
+}
+
+/// Un valor enorme de cache_max_size_mb no debe desbordar (sería un límite mínimo)
+fn max_size_bytes(config: &Config) -> u64 {
+    config.cache_max_size_mb.saturating_mul(1024 * 1024)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_archive(cache: &ArchiveCache, name: &str, size: usize) {
+        fs::write(cache.path_for(name), vec![0u8; size]).unwrap();
+    }
+
+    #[test]
+    fn test_open_holds_cache_lock() {
+        let temp = TempDir::new().unwrap();
+        let mut config = Config::for_tests(temp.path());
+        config.cache_max_size_mb = u64::MAX;
+        config.lock_timeout_secs = 0;
+
+        let cache = ArchiveCache::open(&config).unwrap();
+        assert_eq!(cache.max_size_bytes, u64::MAX);
+        assert!(ArchiveCache::open(&config).is_err());
+        assert!(ArchiveCache::peek(&config).is_ok());
+
+        drop(cache);
+        assert!(ArchiveCache::open(&config).is_ok());
+    }
+
+    #[test]
+    fn test_record_and_reopen() {
+        let temp = TempDir::new().unwrap();
+        let mut cache = ArchiveCache::open_dir(temp.path(), 1024).unwrap();
+        write_archive(&cache, "node-v20.0.0-linux-x64.tar.gz", 100);
+        cache.record("node-v20.0.0-linux-x64.tar.gz", "ABCDEF").unwrap();
+
+        let reopened = ArchiveCache::open_dir(temp.path(), 1024).unwrap();
+        assert!(reopened.contains("node-v20.0.0-linux-x64.tar.gz"));
+        assert_eq!(reopened.checksum_of("node-v20.0.0-linux-x64.tar.gz"), Some("abcdef"));
+        assert_eq!(reopened.total_size(), 100);
+    }
+
+    #[test]
+    fn test_missing_files_are_dropped() {
+        let temp = TempDir::new().unwrap();
+        let mut cache = ArchiveCache::open_dir(temp.path(), 1024).unwrap();
+        write_archive(&cache, "a.tar.gz", 10);
+        cache.record("a.tar.gz", "aa").unwrap();
+        fs::remove_file(cache.path_for("a.tar.gz")).unwrap();
+
+        let reopened = ArchiveCache::open_dir(temp.path(), 1024).unwrap();
+        assert!(!reopened.contains("a.tar.gz"));
+    }
+
+    #[test]
+    fn test_evict_least_recently_used() {
+        let temp = TempDir::new().unwrap();
+        let mut cache = ArchiveCache::open_dir(temp.path(), 250).unwrap();
+        for (name, used) in [("old.tar.gz", 1), ("mid.tar.gz", 2), ("new.tar.gz", 3)] {
+            write_archive(&cache, name, 100);
+            cache.record_at(name, "00", used).unwrap();
+        }
+
+        let evicted = cache.evict().unwrap();
+        assert_eq!(evicted, vec!["old.tar.gz".to_string()]);
+        assert!(!cache.path_for("old.tar.gz").exists());
+        assert!(cache.contains("mid.tar.gz"));
+        assert!(cache.contains("new.tar.gz"));
+        assert_eq!(cache.total_size(), 200);
+    }
+
+    #[test]
+    fn test_zero_budget_evicts_everything() {
+        let temp = TempDir::new().unwrap();
+        let mut cache = ArchiveCache::open_dir(temp.path(), 0).unwrap();
+        write_archive(&cache, "a.tar.gz", 10);
+        cache.record("a.tar.gz", "aa").unwrap();
+
+        cache.evict().unwrap();
+        assert_eq!(cache.len(), 0);
+    }
+}
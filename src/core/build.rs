@@ -0,0 +1,199 @@
+// Build Node.js from a source tarball (configure + make)
+use crate::error::{message, with_context, Result};
+use std::fs::{self, File};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Opciones de compilación para `nvm install --source`
+#[derive(Debug, Clone)]
+pub struct BuildOptions {
+    /// Flags adicionales para ./configure (ej: --with-intl=small-icu)
+    pub configure_flags: Vec<String>,
+    /// Trabajos en paralelo para make (-j)
+    pub jobs: usize,
+    /// Comando make a usar ($MAKE o `make`)
+    pub make: String,
+}
+
+impl BuildOptions {
+    pub fn new(configure_flags: Option<&str>, jobs: Option<usize>) -> Self {
+        let jobs = jobs.filter(|j| *j > 0).unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        });
+
+        BuildOptions {
+            configure_flags: configure_flags
+                .map(|flags| flags.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+            jobs,
+            make: std::env::var("MAKE")
+                .ok()
+                .filter(|m| !m.trim().is_empty())
+                .unwrap_or_else(|| "make".to_string()),
+        }
+    }
+}
+
+/// Compila el árbol de fuentes e instala en `prefix` a través de `destdir`
+/// Devuelve la ruta dentro de `destdir` donde quedó instalado (`destdir` + `prefix`)
+/// Cada paso deja su salida en `log_dir` (configure.log, make.log, install.log)
+pub fn build_node(
+    source_dir: &Path,
+    prefix: &Path,
+    destdir: &Path,
+    options: &BuildOptions,
+    log_dir: &Path,
+) -> Result<PathBuf> {
+    fs::create_dir_all(log_dir)
+        .map_err(|e| with_context(&format!("Failed to create log directory: {}", log_dir.display()), e))?;
+
+    let mut configure = Command::new("sh");
+    configure
+        .arg("./configure")
+        .arg(format!("--prefix={}", prefix.display()))
+        .args(&options.configure_flags);
+    run_step("configure", &mut configure, source_dir, &log_dir.join("configure.log"))?;
+
+    let mut make = Command::new(&options.make);
+    make.arg(format!("-j{}", options.jobs));
+    run_step("make", &mut make, source_dir, &log_dir.join("make.log"))?;
+
+    let mut install = Command::new(&options.make);
+    install
+        .arg("install")
+        .arg(format!("DESTDIR={}", destdir.display()));
+    run_step("make install", &mut install, source_dir, &log_dir.join("install.log"))?;
+
+    // make install con DESTDIR deja los archivos en destdir/<prefix sin raíz>
+    let relative: PathBuf = prefix
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .collect();
+    let installed = destdir.join(relative);
+
+    if !installed.is_dir() {
+        return Err(message(format!(
+            "Build finished but nothing was installed under {}",
+            installed.display()
+        )));
+    }
+
+    Ok(installed)
+}
+
+fn run_step(name: &str, command: &mut Command, dir: &Path, log_path: &Path) -> Result<()> {
+    println!("Running {}... (log: {})", name, log_path.display());
+
+    let log = File::create(log_path)
+        .map_err(|e| with_context(&format!("Failed to create log file: {}", log_path.display()), e))?;
+    let log_err = log
+        .try_clone()
+        .map_err(|e| with_context("Failed to open log file", e))?;
+
+    let status = command
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(log)
+        .stderr(log_err)
+        .status()
+        .map_err(|e| with_context(&format!("Failed to run {}", name), e))?;
+
+    if !status.success() {
+        return Err(message(format!(
+            "{} failed ({}); see {}",
+            name,
+            status,
+            log_path.display()
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    /// Árbol de fuentes falso: configure genera un Makefile que instala un `node` de prueba
+    fn fake_source_tree(dir: &Path, configure_exit: i32) {
+        fs::create_dir_all(dir).unwrap();
+        let configure = format!(
+            r##"#!/bin/sh
+prefix=""
+for arg in "$@"; do
+  case "$arg" in
+    --prefix=*) prefix="${{arg#--prefix=}}" ;;
+  esac
+done
+echo "configure $@"
+[ {exit} -eq 0 ] || exit {exit}
+printf 'PREFIX=%s\nall:\n\t@echo building\ninstall:\n\tmkdir -p $(DESTDIR)$(PREFIX)/bin\n\tprintf "#!/bin/sh\\necho v99.0.0\\n" > $(DESTDIR)$(PREFIX)/bin/node\n\tchmod +x $(DESTDIR)$(PREFIX)/bin/node\n' "$prefix" > Makefile
+"##,
+            exit = configure_exit
+        );
+        let path = dir.join("configure");
+        fs::write(&path, configure).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    fn options() -> BuildOptions {
+        BuildOptions {
+            configure_flags: vec!["--with-intl=none".to_string()],
+            jobs: 2,
+            make: "make".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_build_node_installs_into_destdir() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("node-v99.0.0");
+        fake_source_tree(&source, 0);
+
+        let prefix = temp.path().join("versions").join("v99.0.0");
+        let destdir = temp.path().join("destdir");
+        let logs = temp.path().join("logs");
+
+        let installed = build_node(&source, &prefix, &destdir, &options(), &logs).unwrap();
+        assert!(installed.join("bin").join("node").is_file());
+        assert!(installed.starts_with(&destdir));
+
+        let configure_log = fs::read_to_string(logs.join("configure.log")).unwrap();
+        assert!(configure_log.contains("--with-intl=none"));
+        assert!(logs.join("make.log").is_file());
+        assert!(logs.join("install.log").is_file());
+    }
+
+    #[test]
+    fn test_build_node_reports_failing_step() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("node-v99.0.0");
+        fake_source_tree(&source, 3);
+
+        let err = build_node(
+            &source,
+            &temp.path().join("prefix"),
+            &temp.path().join("destdir"),
+            &options(),
+            &temp.path().join("logs"),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("configure failed"));
+        assert!(err.to_string().contains("configure.log"));
+    }
+
+    #[test]
+    fn test_build_options_parse_flags() {
+        let options = BuildOptions::new(Some("--with-intl=small-icu  --openssl-use-def-ca-store"), Some(4));
+        assert_eq!(
+            options.configure_flags,
+            vec!["--with-intl=small-icu", "--openssl-use-def-ca-store"]
+        );
+        assert_eq!(options.jobs, 4);
+        assert!(BuildOptions::new(None, Some(0)).jobs >= 1);
+    }
+}
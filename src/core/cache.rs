@@ -1,6 +1,7 @@
 use crate::config::Config;
 use crate::core::versions::NodeVersion;
 use crate::error::{with_context, Result};
+use crate::utils::http::{authorized_get, create_client};
 use std::fs;
 use std::time::{Duration, SystemTime};
 
@@ -28,15 +29,30 @@ pub async fn get_cached_versions(config: &Config) -> Result<Vec<NodeVersion>> {
     }
 
     // Si no hay caché válido, descargar y guardar
-    let versions = fetch_remote_versions().await?;
+    let versions = fetch_remote_versions(config).await?;
     save_cache(config, &versions)?;
     Ok(versions)
 }
 
-/// Descarga las versiones desde nodejs.org
-async fn fetch_remote_versions() -> Result<Vec<NodeVersion>> {
-    let url = "https://nodejs.org/dist/index.json";
-    let response = reqwest::get(url).await?;
+/// Lee el índice en caché sin importar su antigüedad y sin usar la red
+/// (vacío si nunca se descargó); lo usan las resoluciones rápidas como `nvm resolve`
+pub fn read_cached_versions(config: &Config) -> Vec<NodeVersion> {
+    fs::read_to_string(config.cache_file())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Descarga el índice de versiones desde el mirror configurado (nodejs.org por defecto)
+async fn fetch_remote_versions(config: &Config) -> Result<Vec<NodeVersion>> {
+    let url = format!("{}/index.json", config.node_mirror.trim_end_matches('/'));
+    let client = create_client(config)?;
+    let response = authorized_get(&client, &url, config)
+        .send()
+        .await
+        .map_err(|e| with_context("Failed to download version index", e))?
+        .error_for_status()
+        .map_err(|e| with_context("Failed to download version index", e))?;
     let versions: Vec<NodeVersion> = response.json().await?;
     Ok(versions)
 }
@@ -58,7 +74,7 @@ fn save_cache(config: &Config, versions: &[NodeVersion]) -> Result<()> {
 /// Fuerza la actualización del caché
 #[allow(dead_code)] // Will be used in Phase 2 (ls-remote update)
 pub async fn update_cache(config: &Config) -> Result<()> {
-    let versions = fetch_remote_versions().await?;
+    let versions = fetch_remote_versions(config).await?;
     save_cache(config, &versions)?;
     Ok(())
 }
@@ -293,13 +309,7 @@ mod tests {
     #[test]
     fn test_get_installed_versions_empty() {
         let temp = TempDir::new().unwrap();
-        let config = Config {
-            nvm_dir: temp.path().to_path_buf(),
-            node_mirror: "".to_string(),
-            arch: "x64".to_string(),
-            cache_duration_minutes: 15,
-            installed_cache_duration_minutes: 5,
-        };
+        let config = Config::for_tests(temp.path());
 
         let versions = get_installed_versions(&config).unwrap();
         assert_eq!(versions.len(), 0);
@@ -308,13 +318,7 @@ fn test_get_installed_versions_empty() {
     #[test]
     fn test_save_and_load_cache() {
         let temp = TempDir::new().unwrap();
-        let config = Config {
-            nvm_dir: temp.path().to_path_buf(),
-            node_mirror: "".to_string(),
-            arch: "x64".to_string(),
-            cache_duration_minutes: 15,
-            installed_cache_duration_minutes: 5,
-        };
+        let config = Config::for_tests(temp.path());
 
         let versions = vec!["v20.0.0".to_string(), "v18.0.0".to_string()];
         save_installed_cache(&config, &versions).unwrap();
@@ -0,0 +1,245 @@
+// Hardlink deduplication of identical files across installed versions
+use crate::error::{with_context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Sufijo del enlace temporal que se renombra sobre el archivo duplicado
+const TEMP_SUFFIX: &str = ".nvm-dedupe";
+
+/// Resultado de una pasada de deduplicación
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DedupeReport {
+    /// Archivos regulares revisados
+    pub files_scanned: usize,
+    /// Archivos reemplazados por un hardlink
+    pub files_linked: usize,
+    /// Bytes liberados (tamaño de los archivos reemplazados)
+    pub bytes_saved: u64,
+    /// Archivos que no se pudieron enlazar (ej: otro sistema de archivos)
+    pub failures: Vec<(PathBuf, String)>,
+}
+
+/// Candidato: archivos con el mismo tamaño y permisos pueden compartir contenido
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct SizeKey {
+    size: u64,
+    /// Modo unix: un hardlink comparte permisos, así que no se mezclan
+    mode: u32,
+}
+
+#[derive(Debug)]
+struct Candidate {
+    path: PathBuf,
+    /// (dispositivo, inodo) para reconocer archivos ya enlazados
+    file_id: Option<(u64, u64)>,
+}
+
+/// Reemplaza por hardlinks los archivos idénticos entre los directorios dados
+/// Solo se consideran archivos regulares (nunca symlinks) con el mismo tamaño, permisos y SHA256;
+/// el reemplazo es atómico (enlace temporal + rename), así un fallo nunca deja un archivo a medias
+pub fn dedupe_dirs(dirs: &[PathBuf]) -> Result<DedupeReport> {
+    let mut report = DedupeReport::default();
+    let mut by_size: HashMap<SizeKey, Vec<Candidate>> = HashMap::new();
+
+    for dir in dirs {
+        collect_files(dir, &mut by_size, &mut report)?;
+    }
+
+    for candidates in by_size.into_values().filter(|c| c.len() > 1) {
+        link_identical(candidates, &mut report)?;
+    }
+
+    Ok(report)
+}
+
+fn collect_files(
+    dir: &Path,
+    by_size: &mut HashMap<SizeKey, Vec<Candidate>>,
+    report: &mut DedupeReport,
+) -> Result<()> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| with_context(&format!("Failed to read {}", dir.display()), e))?;
+
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        let metadata = fs::symlink_metadata(&path)?;
+
+        if metadata.is_dir() {
+            collect_files(&path, by_size, report)?;
+        } else if metadata.is_file() && metadata.len() > 0 {
+            report.files_scanned += 1;
+            let key = SizeKey {
+                size: metadata.len(),
+                mode: file_mode(&metadata),
+            };
+            by_size.entry(key).or_default().push(Candidate {
+                path,
+                file_id: file_id(&metadata),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Agrupa por SHA256 y enlaza cada grupo al primer archivo
+fn link_identical(candidates: Vec<Candidate>, report: &mut DedupeReport) -> Result<()> {
+    let mut by_hash: HashMap<String, Vec<Candidate>> = HashMap::new();
+    // Archivos ya enlazados entre sí solo se leen una vez
+    let mut hashed_ids: HashMap<(u64, u64), String> = HashMap::new();
+
+    for candidate in candidates {
+        let hash = match candidate.file_id.and_then(|id| hashed_ids.get(&id).cloned()) {
+            Some(hash) => hash,
+            None => {
+                let hash = hash_file(&candidate.path)?;
+                if let Some(id) = candidate.file_id {
+                    hashed_ids.insert(id, hash.clone());
+                }
+                hash
+            }
+        };
+        by_hash.entry(hash).or_default().push(candidate);
+    }
+
+    for group in by_hash.into_values().filter(|g| g.len() > 1) {
+        let (original, duplicates) = group.split_first().expect("group has at least two files");
+        for duplicate in duplicates {
+            if duplicate.file_id.is_some() && duplicate.file_id == original.file_id {
+                continue;
+            }
+
+            match replace_with_link(&original.path, &duplicate.path) {
+                Ok(size) => {
+                    report.files_linked += 1;
+                    report.bytes_saved += size;
+                }
+                Err(e) => report.failures.push((duplicate.path.clone(), e.to_string())),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn replace_with_link(original: &Path, duplicate: &Path) -> io::Result<u64> {
+    let size = fs::metadata(duplicate)?.len();
+
+    let mut temp = duplicate.as_os_str().to_owned();
+    temp.push(TEMP_SUFFIX);
+    let temp = PathBuf::from(temp);
+    if temp.exists() {
+        fs::remove_file(&temp)?;
+    }
+
+    fs::hard_link(original, &temp)?;
+    if let Err(e) = fs::rename(&temp, duplicate) {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    Ok(size)
+}
+
+fn hash_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path)
+        .map_err(|e| with_context(&format!("Failed to open {}", path.display()), e))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)
+        .map_err(|e| with_context(&format!("Failed to read {}", path.display()), e))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode()
+}
+
+#[cfg(not(unix))]
+fn file_mode(metadata: &fs::Metadata) -> u32 {
+    u32::from(metadata.permissions().readonly())
+}
+
+/// Identidad (dispositivo, inodo) de un archivo; varios hardlinks comparten la misma
+#[cfg(unix)]
+pub fn file_id(metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+pub fn file_id(_metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_dedupe_links_identical_files() {
+        let temp = TempDir::new().unwrap();
+        let v18 = temp.path().join("v18.0.0");
+        let v20 = temp.path().join("v20.0.0");
+        let header = "#define NODE_API 1\n".repeat(50);
+        write(&v18.join("include/node/node_api.h"), &header);
+        write(&v20.join("include/node/node_api.h"), &header);
+        write(&v18.join("bin/node"), "node 18");
+        write(&v20.join("bin/node"), "node 20");
+
+        let report = dedupe_dirs(&[v18.clone(), v20.clone()]).unwrap();
+        assert_eq!(report.files_scanned, 4);
+        assert_eq!(report.files_linked, 1);
+        assert_eq!(report.bytes_saved, header.len() as u64);
+        assert!(report.failures.is_empty());
+        assert_eq!(fs::read_to_string(v20.join("bin/node")).unwrap(), "node 20");
+
+        // Una segunda pasada no encuentra nada nuevo
+        let report = dedupe_dirs(&[v18.clone(), v20.clone()]).unwrap();
+        assert_eq!(report.files_linked, 0);
+
+        // Desinstalar una versión no afecta al archivo compartido de la otra
+        fs::remove_dir_all(&v18).unwrap();
+        assert_eq!(fs::read_to_string(v20.join("include/node/node_api.h")).unwrap(), header);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dedupe_keeps_different_permissions_and_symlinks() {
+        use std::os::unix::fs::{symlink, MetadataExt, PermissionsExt};
+
+        let temp = TempDir::new().unwrap();
+        let a = temp.path().join("v18.0.0");
+        let b = temp.path().join("v20.0.0");
+        write(&a.join("bin/tool"), "#!/bin/sh\n");
+        write(&b.join("bin/tool"), "#!/bin/sh\n");
+        fs::set_permissions(a.join("bin/tool"), fs::Permissions::from_mode(0o755)).unwrap();
+        fs::set_permissions(b.join("bin/tool"), fs::Permissions::from_mode(0o644)).unwrap();
+        write(&a.join("lib/cli.js"), "module.exports = 1;\n");
+        write(&b.join("lib/cli.js"), "module.exports = 1;\n");
+        symlink("../lib/cli.js", b.join("bin/npm")).unwrap();
+
+        let report = dedupe_dirs(&[a.clone(), b.clone()]).unwrap();
+        assert_eq!(report.files_linked, 1);
+        assert_eq!(
+            fs::metadata(a.join("lib/cli.js")).unwrap().ino(),
+            fs::metadata(b.join("lib/cli.js")).unwrap().ino()
+        );
+        assert_ne!(
+            fs::metadata(a.join("bin/tool")).unwrap().ino(),
+            fs::metadata(b.join("bin/tool")).unwrap().ino()
+        );
+        assert!(fs::symlink_metadata(b.join("bin/npm")).unwrap().file_type().is_symlink());
+    }
+}
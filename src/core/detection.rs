@@ -48,6 +48,37 @@ pub fn detect_system_node() -> Option<SystemNodeInfo> {
     find_node_in_system_locations()
 }
 
+/// Entradas del PATH que no gestiona nvm (sin versiones, link global ni shims)
+pub fn unmanaged_path(config: &crate::config::Config) -> Vec<PathBuf> {
+    let managed = [config.shims_dir(), config.nvm_node_dir()];
+    env::var_os("PATH")
+        .map(|path| env::split_paths(&path).collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|entry| !managed.contains(entry) && !entry.starts_with(config.versions_dir()))
+        .collect()
+}
+
+/// Node.js del sistema: el primero del PATH fuera de las rutas de nvm (`nvm use system`)
+pub fn find_system_node(config: &crate::config::Config) -> Option<SystemNodeInfo> {
+    let exe = if cfg!(target_os = "windows") { "node.exe" } else { "node" };
+
+    unmanaged_path(config).into_iter().find_map(|dir| {
+        let path = dir.join(exe);
+        if !path.is_file() {
+            return None;
+        }
+        let version = get_node_version(&path)?;
+        let npm_version = get_npm_version(&path);
+        Some(SystemNodeInfo {
+            path,
+            version,
+            npm_version,
+            source: DetectionSource::PathEnvironment,
+        })
+    })
+}
+
 /// Buscar Node.js en PATH
 fn find_node_in_path() -> Option<SystemNodeInfo> {
     let cmd = if cfg!(target_os = "windows") {
@@ -6,28 +6,76 @@
 use std::path::{Path, PathBuf};
 
 use crate::config::Config;
+use crate::core::archive_cache::ArchiveCache;
 use crate::core::versions::NodeVersion;
-use crate::utils::http::create_client;
+use crate::utils::auth::redact_url;
+use crate::utils::http::{authorized_get, create_client};
 
-/// Construye la URL de descarga para una versión de Node.js
-pub fn get_download_url(version: &NodeVersion, config: &Config) -> String {
+/// Nombres de archivo candidatos para la plataforma actual, en orden de preferencia
+/// En Linux/macOS se prefiere .tar.xz (≈30% más pequeño) y se recurre a .tar.gz
+pub fn get_archive_filenames(version: &NodeVersion, config: &Config) -> Vec<String> {
     let arch = &config.arch;
     let version_str = &version.version;
     
     #[cfg(target_os = "windows")]
-    let filename = format!("node-{}-win-{}.zip", version_str, arch);
+    let filenames = vec![format!("node-{}-win-{}.zip", version_str, arch)];
     
     #[cfg(not(target_os = "windows"))]
-    let filename = {
+    let filenames = {
         #[cfg(target_os = "macos")]
         let os = "darwin";
         #[cfg(target_os = "linux")]
         let os = "linux";
         
-        format!("node-{}-{}-{}.tar.gz", version_str, os, arch)
+        let base = format!("node-{}-{}-{}", version_str, os, arch);
+        let mut names = Vec::new();
+        if supports_xz(version, os, arch) {
+            names.push(format!("{}.tar.xz", base));
+        }
+        names.push(format!("{}.tar.gz", base));
+        names
     };
     
-    format!("{}/{}/{}", config.node_mirror, version_str, filename)
+    filenames
+}
+
+/// Indica si la versión publica un .tar.xz para la plataforma según su lista `files`
+/// El índice lista `linux-x64`, `osx-arm64-tar`, etc.; Node.js publica .tar.xz desde v4
+#[cfg(not(target_os = "windows"))]
+fn supports_xz(version: &NodeVersion, os: &str, arch: &str) -> bool {
+    let platform = if os == "darwin" {
+        format!("osx-{}", arch)
+    } else {
+        format!("{}-{}", os, arch)
+    };
+    
+    let mut listed = false;
+    for file in &version.files {
+        let file = file.to_lowercase();
+        if !file.starts_with(&platform) {
+            continue;
+        }
+        // Algunos mirrors listan el formato explícitamente (p. ej. `linux-x64-tar-xz`)
+        if file.ends_with("xz") {
+            return true;
+        }
+        if file == platform || file == format!("{}-tar", platform) {
+            listed = true;
+        }
+    }
+    
+    let major = super::versions::version_number(&version.version)
+        .split('.')
+        .next()
+        .and_then(|m| m.parse::<u32>().ok())
+        .unwrap_or(0);
+    
+    listed && major >= 4
+}
+
+/// Construye la URL de un archivo publicado para una versión
+pub fn get_archive_url(version: &str, filename: &str, config: &Config) -> String {
+    format!("{}/{}/{}", config.node_mirror, version, filename)
 }
 
 /// Construye la URL del archivo SHASUMS256.txt
@@ -41,13 +89,14 @@ pub async fn get_expected_checksum(
     filename: &str,
     config: &Config,
 ) -> Result<String> {
-    let client = create_client().map_err(|e| with_context("Failed to create HTTP client", e))?;
+    let client = create_client(config)?;
     let url = get_checksum_url(version, config);
     
-    let response = client
-        .get(&url)
+    let response = authorized_get(&client, &url, config)
         .send()
         .await
+        .map_err(|e| with_context("Failed to download SHASUMS256.txt", e))?
+        .error_for_status()
         .map_err(|e| with_context("Failed to download SHASUMS256.txt", e))?;
     
     let content = response
@@ -95,39 +144,100 @@ pub fn verify_checksum(path: &Path, expected: &str) -> Result<()> {
 }
 
 /// Descarga un archivo de Node.js con barra de progreso
-pub async fn download_node_archive(
-    version: &NodeVersion,
-    dest_dir: &Path,
-    config: &Config,
-) -> Result<PathBuf> {
-    let client = create_client().map_err(|e| with_context("Failed to create HTTP client", e))?;
-    let url = get_download_url(version, config);
+/// Si el archivo ya está en el caché y su checksum coincide, no se descarga de nuevo
+pub async fn download_node_archive(version: &NodeVersion, config: &Config) -> Result<PathBuf> {
+    let client = create_client(config)?;
+    let filenames = get_archive_filenames(version, config);
+    let mut cache = ArchiveCache::open(config)?;
     
-    // Extraer el nombre del archivo de la URL
-    let filename = url.split('/').next_back().unwrap();
-    let dest_path = dest_dir.join(filename);
-    
-    // Si el archivo ya existe, verificar si es válido
-    if dest_path.exists() {
+    // Si algún formato ya está en el caché, verificar si es válido
+    for filename in &filenames {
+        let dest_path = cache.path_for(filename);
+        if !dest_path.exists() {
+            continue;
+        }
+        
         println!("Archive already exists, verifying...");
         
-        // Intentar verificar checksum
-        if let Ok(expected) = get_expected_checksum(&version.version, filename, config).await {
+        // El checksum publicado tiene prioridad; sin red se usa el registrado al descargarlo
+        let expected = match get_expected_checksum(&version.version, filename, config).await {
+            Ok(expected) => Some(expected),
+            Err(_) => cache.checksum_of(filename).map(str::to_string),
+        };
+        
+        if let Some(expected) = expected {
             if verify_checksum(&dest_path, &expected).is_ok() {
-                println!("Archive verified, skipping download");
+                println!("Using cached archive, skipping download");
+                cache.record(filename, &expected)?;
                 return Ok(dest_path);
             }
         }
         
         println!("Archive invalid, re-downloading...");
-        std::fs::remove_file(&dest_path)?;
+        cache.remove(filename)?;
+    }
+    
+    for (idx, filename) in filenames.iter().enumerate() {
+        let url = get_archive_url(&version.version, filename, config);
+        let dest_path = cache.path_for(filename);
+        
+        println!("Downloading from: {}", redact_url(&url));
+        
+        // Hacer la solicitud inicial para obtener el tamaño
+        let response = authorized_get(&client, &url, config)
+            .send()
+            .await
+            .map_err(|e| with_context("Failed to start download", e))?;
+        
+        // Si el mirror no tiene este formato, probar el siguiente
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            if let Some(next) = filenames.get(idx + 1) {
+                println!("{} not available, falling back to {}", filename, next);
+                continue;
+            }
+        }
+        
+        if !response.status().is_success() {
+            return Err(message(format!(
+                "Download failed with status: {}",
+                response.status()
+            )));
+        }
+        
+        save_response(response, &dest_path).await?;
+        
+        println!("Saved to: {}", dest_path.display());
+        
+        // Verificar checksum
+        println!("Verifying checksum...");
+        match get_expected_checksum(&version.version, filename, config).await {
+            Ok(expected) => {
+                verify_checksum(&dest_path, &expected)
+                    .map_err(|e| with_context("Checksum verification failed", e))?;
+                println!("Checksum verified ✓");
+                cache.record(filename, &expected)?;
+            }
+            Err(e) => {
+                println!("Warning: Could not verify checksum: {}", e);
+            }
+        }
+        
+        return Ok(dest_path);
     }
     
-    println!("Downloading from: {}", url);
+    Err(message(format!(
+        "No archive available for {} on this platform",
+        version.version
+    )))
+}
+
+/// Descarga una URL arbitraria (p. ej. `nvm install --from-url`) a `dest_path`
+pub async fn download_url_to_file(url: &str, dest_path: &Path, config: &Config) -> Result<()> {
+    let client = create_client(config)?;
+    
+    println!("Downloading from: {}", redact_url(url));
     
-    // Hacer la solicitud inicial para obtener el tamaño
-    let response = client
-        .get(&url)
+    let response = authorized_get(&client, url, config)
         .send()
         .await
         .map_err(|e| with_context("Failed to start download", e))?;
@@ -139,6 +249,14 @@ pub async fn download_node_archive(
         )));
     }
     
+    save_response(response, dest_path).await?;
+    println!("Saved to: {}", dest_path.display());
+    
+    Ok(())
+}
+
+/// Guarda el cuerpo de una respuesta en disco mostrando una barra de progreso
+async fn save_response(response: reqwest::Response, dest_path: &Path) -> Result<()> {
     let total_size = response.content_length().unwrap_or(0);
     
     // Crear barra de progreso
@@ -166,34 +284,25 @@ pub async fn download_node_archive(
     }
     
     // Escribir archivo
-    let mut file = File::create(&dest_path)
+    let mut file = File::create(dest_path)
         .map_err(|e| with_context(&format!("Failed to create file: {}", dest_path.display()), e))?;
     
     file.write_all(&content)
         .map_err(|e| with_context("Failed to write downloaded content", e))?;
     
-    println!("Saved to: {}", dest_path.display());
-    
-    // Verificar checksum
-    println!("Verifying checksum...");
-    match get_expected_checksum(&version.version, filename, config).await {
-        Ok(expected) => {
-            verify_checksum(&dest_path, &expected)
-                .map_err(|e| with_context("Checksum verification failed", e))?;
-            println!("Checksum verified ✓");
-        }
-        Err(e) => {
-            println!("Warning: Could not verify checksum: {}", e);
-        }
-    }
-    
-    Ok(dest_path)
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// URL de descarga preferida (el primer candidato de `get_archive_filenames`)
+    fn get_download_url(version: &NodeVersion, config: &Config) -> String {
+        let filename = get_archive_filenames(version, config).remove(0);
+        get_archive_url(&version.version, &filename, config)
+    }
+
     #[test]
     fn test_get_download_url_windows() {
         let config = Config::new().unwrap();
@@ -220,6 +329,47 @@ fn test_get_download_url_windows() {
         assert!(url.contains(".tar.gz"));
     }
 
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_archive_filenames_prefer_xz() {
+        let config = Config::new().unwrap();
+        let mut version = NodeVersion {
+            version: "v20.10.0".to_string(),
+            lts: crate::core::versions::LtsInfo::Bool(false),
+            files: vec![],
+            date: "".to_string(),
+            npm: None,
+            v8: None,
+            uv: None,
+            zlib: None,
+            openssl: None,
+            modules: None,
+            security: false,
+        };
+
+        // Sin información en `files`, solo .tar.gz
+        let names = get_archive_filenames(&version, &config);
+        assert_eq!(names.len(), 1);
+        assert!(names[0].ends_with(".tar.gz"));
+
+        #[cfg(target_os = "macos")]
+        let platform = format!("osx-{}-tar", config.arch);
+        #[cfg(target_os = "linux")]
+        let platform = format!("linux-{}", config.arch);
+
+        version.files = vec![platform.clone(), "src".to_string()];
+        let names = get_archive_filenames(&version, &config);
+        assert_eq!(names.len(), 2);
+        assert!(names[0].ends_with(".tar.xz"));
+        assert!(names[1].ends_with(".tar.gz"));
+
+        // Versiones anteriores a v4 no publican .tar.xz
+        version.version = "v0.12.18".to_string();
+        let names = get_archive_filenames(&version, &config);
+        assert_eq!(names.len(), 1);
+        assert!(names[0].ends_with(".tar.gz"));
+    }
+
     #[test]
     fn test_get_checksum_url() {
         let config = Config::new().unwrap();
@@ -1,31 +1,104 @@
 use crate::error::{message, with_context, Result};
+use flate2::read::GzDecoder;
 use std::fs;
-use std::path::{Path, PathBuf};
-
-#[cfg(target_os = "windows")]
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use tar::{Archive, EntryType};
+use xz2::read::XzDecoder;
 use zip::ZipArchive;
 
-#[cfg(not(target_os = "windows"))]
-use flate2::read::GzDecoder;
-#[cfg(not(target_os = "windows"))]
-use tar::Archive;
-
 /// Extrae un archivo comprimido al directorio de destino
+/// El formato se elige por la extensión (.zip, .tar.xz o .tar.gz)
 pub fn extract_archive(archive_path: &Path, dest_dir: &Path) -> Result<PathBuf> {
     println!("Extracting to: {}", dest_dir.display());
 
-    #[cfg(target_os = "windows")]
-    {
+    let name = archive_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    if name.ends_with(".zip") {
         extract_zip(archive_path, dest_dir)
+    } else {
+        extract_tarball(archive_path, dest_dir)
     }
+}
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        extract_tar_gz(archive_path, dest_dir)
+/// Valida la ruta de una entrada: debe ser relativa y sin componentes `..`
+/// Devuelve la ruta normalizada (sin `.`), que puede quedar vacía para la entrada raíz
+fn safe_entry_path(path: &Path) -> Result<PathBuf> {
+    let mut clean = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => clean.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(message(format!(
+                    "Unsafe path in archive: {}",
+                    path.display()
+                )));
+            }
+        }
+    }
+    Ok(clean)
+}
+
+/// Comprueba que `path` (ya existente) está dentro de `dest_root` una vez resueltos los symlinks
+fn ensure_inside(dest_root: &Path, path: &Path) -> Result<()> {
+    let real = path.canonicalize().map_err(|e| {
+        with_context(&format!("Failed to resolve path: {}", path.display()), e)
+    })?;
+    if real.starts_with(dest_root) {
+        Ok(())
+    } else {
+        Err(message(format!(
+            "Archive entry escapes destination directory: {}",
+            path.display()
+        )))
+    }
+}
+
+/// Crea el directorio padre de `outpath` y verifica que no sale del destino
+fn prepare_parent(dest_root: &Path, outpath: &Path) -> Result<()> {
+    if let Some(parent) = outpath.parent() {
+        if !parent.exists() {
+            fs::create_dir_all(parent).map_err(|e| {
+                with_context(
+                    &format!("Failed to create parent directory: {}", parent.display()),
+                    e,
+                )
+            })?;
+        }
+        ensure_inside(dest_root, parent)?;
     }
+    Ok(())
+}
+
+#[cfg(unix)]
+/// Resuelve el destino de un symlink relativo a `base`, siguiendo los symlinks existentes
+/// Devuelve `None` si el destino es absoluto o sube por encima de la raíz
+fn resolve_link_target(base: &Path, target: &Path) -> Option<PathBuf> {
+    let mut resolved = base.to_path_buf();
+    for component in target.components() {
+        match component {
+            Component::Normal(part) => {
+                resolved.push(part);
+                if let Ok(real) = resolved.canonicalize() {
+                    resolved = real;
+                }
+            }
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    return None;
+                }
+            }
+            Component::CurDir => {}
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(resolved)
 }
 
-#[cfg(target_os = "windows")]
 fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<PathBuf> {
     let file = fs::File::open(archive_path).map_err(|e| {
         with_context(
@@ -40,6 +113,9 @@ fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<PathBuf> {
     // Crear directorio de destino
     fs::create_dir_all(dest_dir)
         .map_err(|e| with_context("Failed to create destination directory", e))?;
+    let dest_root = dest_dir
+        .canonicalize()
+        .map_err(|e| with_context("Failed to resolve destination directory", e))?;
 
     let mut extracted_root = None;
 
@@ -47,30 +123,37 @@ fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<PathBuf> {
         let mut file = archive
             .by_index(i)
             .map_err(|e| with_context("Failed to read ZIP entry", e))?;
-        let outpath = match file.enclosed_name() {
-            Some(path) => dest_dir.join(path),
-            None => continue,
-        };
+        let relative = safe_entry_path(Path::new(file.name()))?;
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+        let outpath = dest_dir.join(&relative);
 
         // Guardar el primer directorio como raíz extraída
         if extracted_root.is_none() {
-            if let Some(first_component) = file.name().split('/').next() {
-                extracted_root = Some(dest_dir.join(first_component));
+            if let Some(first_component) = relative.components().next() {
+                extracted_root = Some(dest_dir.join(first_component.as_os_str()));
             }
         }
 
-        if file.name().ends_with('/') {
+        // Los symlinks en ZIP son archivos con modo S_IFLNK: no se siguen ni se crean
+        if file
+            .unix_mode()
+            .map(|mode| mode & 0o170000 == 0o120000)
+            .unwrap_or(false)
+        {
+            println!("  Skipping symlink entry: {}", relative.display());
+            continue;
+        }
+
+        if file.is_dir() {
             // Es un directorio
             fs::create_dir_all(&outpath)
                 .map_err(|e| with_context("Failed to create directory", e))?;
+            ensure_inside(&dest_root, &outpath)?;
         } else {
             // Es un archivo
-            if let Some(p) = outpath.parent() {
-                if !p.exists() {
-                    fs::create_dir_all(p)
-                        .map_err(|e| with_context("Failed to create parent directory", e))?;
-                }
-            }
+            prepare_parent(&dest_root, &outpath)?;
 
             let mut outfile = fs::File::create(&outpath).map_err(|e| {
                 with_context(
@@ -81,6 +164,14 @@ fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<PathBuf> {
 
             std::io::copy(&mut file, &mut outfile)
                 .map_err(|e| with_context("Failed to extract file", e))?;
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                if let Some(mode) = file.unix_mode() {
+                    let _ = fs::set_permissions(&outpath, fs::Permissions::from_mode(mode & 0o777));
+                }
+            }
         }
     }
 
@@ -89,8 +180,16 @@ fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<PathBuf> {
     extracted_root.ok_or_else(|| message("No files extracted from archive"))
 }
 
-#[cfg(not(target_os = "windows"))]
-fn extract_tar_gz(archive_path: &Path, dest_dir: &Path) -> Result<PathBuf> {
+/// Enlace pendiente; se crean al final para que ningún archivo se escriba a través de ellos
+struct PendingLink {
+    outpath: PathBuf,
+    target: PathBuf,
+    hard: bool,
+}
+
+/// Extrae un .tar.xz o .tar.gz; el descompresor se elige por la extensión
+/// Rechaza rutas absolutas, componentes `..` y enlaces que apunten fuera del destino
+fn extract_tarball(archive_path: &Path, dest_dir: &Path) -> Result<PathBuf> {
     let file = fs::File::open(archive_path).map_err(|e| {
         with_context(
             &format!("Failed to open archive: {}", archive_path.display()),
@@ -98,14 +197,26 @@ fn extract_tar_gz(archive_path: &Path, dest_dir: &Path) -> Result<PathBuf> {
         )
     })?;
 
-    let tar = GzDecoder::new(file);
+    let name = archive_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let tar: Box<dyn Read> = if name.ends_with(".tar.xz") || name.ends_with(".txz") {
+        Box::new(XzDecoder::new(file))
+    } else {
+        Box::new(GzDecoder::new(file))
+    };
     let mut archive = Archive::new(tar);
 
     // Crear directorio de destino
     fs::create_dir_all(dest_dir)
         .map_err(|e| with_context("Failed to create destination directory", e))?;
+    let dest_root = dest_dir
+        .canonicalize()
+        .map_err(|e| with_context("Failed to resolve destination directory", e))?;
 
     let mut extracted_root = None;
+    let mut links = Vec::new();
 
     // Extraer archivos
     let mut entry_count = 0;
@@ -114,10 +225,14 @@ fn extract_tar_gz(archive_path: &Path, dest_dir: &Path) -> Result<PathBuf> {
         .map_err(|e| with_context("Failed to read tar entries", e))?
     {
         let mut entry = entry.map_err(|e| with_context("Failed to read tar entry", e))?;
-        let path = entry
+        let raw_path = entry
             .path()
             .map_err(|e| with_context("Failed to get entry path", e))?
             .to_path_buf();
+        let path = safe_entry_path(&raw_path)?;
+        if path.as_os_str().is_empty() {
+            continue;
+        }
 
         // Guardar el primer directorio como raíz extraída
         if extracted_root.is_none() {
@@ -139,29 +254,10 @@ fn extract_tar_gz(archive_path: &Path, dest_dir: &Path) -> Result<PathBuf> {
             );
         }
 
-        // Crear directorio padre si no existe
-        if let Some(parent) = outpath.parent() {
-            // Solo crear si no existe ya
-            if !parent.exists() {
-                if entry_count <= 5 {
-                    println!("    Creating parent: {}", parent.display());
-                }
-                fs::create_dir_all(parent).map_err(|e| {
-                    with_context(
-                        &format!(
-                            "Failed to create parent directory: {}",
-                            parent.display()
-                        ),
-                        e,
-                    )
-                })?;
-            }
-        } else {
-            println!("    WARNING: No parent for {}", outpath.display());
-        }
+        let entry_type = entry.header().entry_type();
 
-        // Manejar directorios y archivos por separado
-        if entry.header().entry_type().is_dir() {
+        // Manejar directorios, enlaces y archivos por separado
+        if entry_type.is_dir() {
             // Para directorios, asegurar que existen
             if !outpath.exists() {
                 if entry_count <= 5 {
@@ -174,23 +270,54 @@ fn extract_tar_gz(archive_path: &Path, dest_dir: &Path) -> Result<PathBuf> {
                     )
                 })?;
             }
-        } else {
-            // Para archivos, usar unpack
-            if entry_count <= 5 {
-                println!("    Unpacking file");
+            ensure_inside(&dest_root, &outpath)?;
+            continue;
+        }
+
+        if entry_type.is_symlink() || entry_type.is_hard_link() {
+            let target = entry
+                .link_name()
+                .map_err(|e| with_context("Failed to get link target", e))?
+                .ok_or_else(|| message(format!("Link without target: {}", path.display())))?
+                .to_path_buf();
+            links.push(PendingLink {
+                outpath,
+                target,
+                hard: entry_type.is_hard_link(),
+            });
+            continue;
+        }
+
+        if entry_type != EntryType::Regular && entry_type != EntryType::Continuous {
+            println!("  Skipping unsupported entry: {}", path.display());
+            continue;
+        }
+
+        prepare_parent(&dest_root, &outpath)?;
+
+        // Nunca escribir a través de un enlace existente
+        if let Ok(metadata) = fs::symlink_metadata(&outpath) {
+            if metadata.file_type().is_symlink() {
+                fs::remove_file(&outpath)
+                    .map_err(|e| with_context("Failed to replace existing link", e))?;
             }
-            entry.unpack(&outpath).map_err(|e| {
-                with_context(
-                    &format!(
-                        "Failed to extract file: {} to {}",
-                        path.display(),
-                        outpath.display()
-                    ),
-                    e,
-                )
-            })?;
         }
 
+        // Para archivos, usar unpack
+        if entry_count <= 5 {
+            println!("    Unpacking file");
+        }
+        entry.unpack(&outpath).map_err(|e| {
+            with_context(
+                &format!(
+                    "Failed to extract file: {} to {}",
+                    path.display(),
+                    outpath.display()
+                ),
+                e,
+            )
+        })?;
+
         // En Unix, preservar permisos ejecutables
         #[cfg(unix)]
         {
@@ -208,11 +335,76 @@ fn extract_tar_gz(archive_path: &Path, dest_dir: &Path) -> Result<PathBuf> {
         }
     }
 
+    create_links(&dest_root, &links)?;
+
     println!("Extraction complete");
 
     extracted_root.ok_or_else(|| message("No files extracted from archive"))
 }
 
+/// Crea los enlaces pendientes y verifica que ninguno apunte fuera del destino
+/// La verificación se hace con todos los enlaces creados para detectar cadenas de symlinks
+fn create_links(dest_root: &Path, links: &[PendingLink]) -> Result<()> {
+    for link in links {
+        prepare_parent(dest_root, &link.outpath)?;
+        if fs::symlink_metadata(&link.outpath).is_ok() {
+            fs::remove_file(&link.outpath)
+                .map_err(|e| with_context("Failed to replace existing entry", e))?;
+        }
+
+        if link.hard {
+            // El destino de un hard link es relativo a la raíz del archivo
+            let source = dest_root.join(safe_entry_path(&link.target)?);
+            if !source.is_file() {
+                return Err(message(format!(
+                    "Hard link target not found in archive: {}",
+                    link.target.display()
+                )));
+            }
+            ensure_inside(dest_root, &source)?;
+            fs::hard_link(&source, &link.outpath).map_err(|e| {
+                with_context(
+                    &format!("Failed to create hard link: {}", link.outpath.display()),
+                    e,
+                )
+            })?;
+            continue;
+        }
+
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&link.target, &link.outpath).map_err(|e| {
+            with_context(
+                &format!("Failed to create symlink: {}", link.outpath.display()),
+                e,
+            )
+        })?;
+
+        #[cfg(not(unix))]
+        println!("  Skipping symlink entry: {}", link.outpath.display());
+    }
+
+    #[cfg(unix)]
+    for link in links.iter().filter(|link| !link.hard) {
+        let base = link
+            .outpath
+            .parent()
+            .and_then(|parent| parent.canonicalize().ok())
+            .unwrap_or_else(|| dest_root.to_path_buf());
+        let inside = resolve_link_target(&base, &link.target)
+            .map(|resolved| resolved.starts_with(dest_root))
+            .unwrap_or(false);
+        if !inside {
+            return Err(message(format!(
+                "Symlink points outside destination directory: {} -> {}",
+                link.outpath.display(),
+                link.target.display()
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 /// Mueve el contenido extraído a la ubicación final
 /// Node.js típicamente extrae a un directorio como "node-v20.10.0-win-x64"
 /// pero queremos moverlo a "v20.10.0"
@@ -278,6 +470,230 @@ fn test_extract_archive_structure() {
         let _ = fs::remove_dir_all(&temp_dir);
     }
 
+    #[cfg(not(target_os = "windows"))]
+    fn build_tarball(path: &Path) {
+        let mut builder = tar::Builder::new(Vec::new());
+        let content = b"#!/bin/sh\necho node\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "node-v20.0.0-linux-x64/bin/node", &content[..])
+            .unwrap();
+        let data = builder.into_inner().unwrap();
+
+        let file = fs::File::create(path).unwrap();
+        if path.to_string_lossy().ends_with(".xz") {
+            let mut encoder = xz2::write::XzEncoder::new(file, 6);
+            encoder.write_all(&data).unwrap();
+            encoder.finish().unwrap();
+        } else {
+            let mut encoder =
+                flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            encoder.write_all(&data).unwrap();
+            encoder.finish().unwrap();
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_extract_xz_and_gz_tarballs() {
+        for ext in ["tar.xz", "tar.gz"] {
+            let temp = tempfile::TempDir::new().unwrap();
+            let archive = temp.path().join(format!("node.{}", ext));
+            build_tarball(&archive);
+
+            let dest = temp.path().join("out");
+            let root = extract_archive(&archive, &dest).unwrap();
+            assert_eq!(root, dest.join("node-v20.0.0-linux-x64"));
+
+            let node = root.join("bin").join("node");
+            assert!(node.is_file(), "node should be extracted from {}", ext);
+
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&node).unwrap().permissions().mode();
+            assert!(mode & 0o111 != 0, "node should stay executable");
+        }
+    }
+
+    /// Entrada construida a mano: `tar::Builder` no permite escribir rutas inseguras
+    #[cfg(unix)]
+    fn raw_entry(
+        builder: &mut tar::Builder<Vec<u8>>,
+        name: &str,
+        entry_type: EntryType,
+        link: Option<&str>,
+        data: &[u8],
+    ) {
+        let mut header = tar::Header::new_gnu();
+        header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+        header.set_entry_type(entry_type);
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        if let Some(link) = link {
+            header.set_link_name(link).unwrap();
+        }
+        header.set_cksum();
+        builder.append(&header, data).unwrap();
+    }
+
+    /// Escribe un .tar.gz con las entradas dadas y lo extrae en `<temp>/dest`
+    #[cfg(unix)]
+    fn extract_crafted(
+        temp: &tempfile::TempDir,
+        entries: &[(&str, EntryType, Option<&str>)],
+    ) -> Result<PathBuf> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (name, entry_type, link) in entries {
+            let data: &[u8] = if *entry_type == EntryType::Regular { b"pwned" } else { b"" };
+            raw_entry(&mut builder, name, *entry_type, *link, data);
+        }
+        let data = builder.into_inner().unwrap();
+
+        let archive = temp.path().join("crafted.tar.gz");
+        let file = fs::File::create(&archive).unwrap();
+        let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        encoder.write_all(&data).unwrap();
+        encoder.finish().unwrap();
+
+        extract_archive(&archive, &temp.path().join("dest"))
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rejects_parent_dir_entries() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let result = extract_crafted(
+            &temp,
+            &[("node-v1.0.0/../../evil.txt", EntryType::Regular, None)],
+        );
+        assert!(result.is_err());
+        assert!(!temp.path().join("evil.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rejects_absolute_entries() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let target = temp.path().join("abs-evil.txt");
+        let name = target.to_string_lossy().to_string();
+        let result = extract_crafted(&temp, &[(name.as_str(), EntryType::Regular, None)]);
+        assert!(result.is_err());
+        assert!(!target.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rejects_escaping_symlinks() {
+        for target in ["../../outside", "/etc/passwd"] {
+            let temp = tempfile::TempDir::new().unwrap();
+            let result = extract_crafted(
+                &temp,
+                &[
+                    ("node-v1.0.0/", EntryType::Directory, None),
+                    ("node-v1.0.0/link", EntryType::Symlink, Some(target)),
+                ],
+            );
+            assert!(result.is_err(), "symlink to {} should be rejected", target);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rejects_symlink_chains() {
+        // Cada enlace por separado parece quedarse dentro; juntos salen del destino
+        let temp = tempfile::TempDir::new().unwrap();
+        let result = extract_crafted(
+            &temp,
+            &[
+                ("node-v1.0.0/", EntryType::Directory, None),
+                ("node-v1.0.0/t", EntryType::Symlink, Some("s/../..")),
+                ("node-v1.0.0/s", EntryType::Symlink, Some("..")),
+            ],
+        );
+        assert!(result.is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_no_writes_through_symlinks() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let outside = temp.path().join("outside");
+        fs::create_dir_all(&outside).unwrap();
+        let outside_str = outside.to_string_lossy().to_string();
+
+        let result = extract_crafted(
+            &temp,
+            &[
+                ("node-v1.0.0/", EntryType::Directory, None),
+                ("node-v1.0.0/link", EntryType::Symlink, Some(outside_str.as_str())),
+                ("node-v1.0.0/link/evil.txt", EntryType::Regular, None),
+            ],
+        );
+        assert!(result.is_err());
+        assert!(!outside.join("evil.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rejects_escaping_hard_links() {
+        let temp = tempfile::TempDir::new().unwrap();
+        fs::write(temp.path().join("secret"), "secret").unwrap();
+        let result = extract_crafted(
+            &temp,
+            &[
+                ("node-v1.0.0/", EntryType::Directory, None),
+                ("node-v1.0.0/hard", EntryType::Link, Some("../secret")),
+            ],
+        );
+        assert!(result.is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_allows_internal_links() {
+        // Estructura real de Node.js: bin/npm -> ../lib/node_modules/npm/bin/npm-cli.js
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = extract_crafted(
+            &temp,
+            &[
+                ("node-v1.0.0/", EntryType::Directory, None),
+                ("node-v1.0.0/lib/node_modules/npm/bin/npm-cli.js", EntryType::Regular, None),
+                (
+                    "node-v1.0.0/bin/npm",
+                    EntryType::Symlink,
+                    Some("../lib/node_modules/npm/bin/npm-cli.js"),
+                ),
+                (
+                    "node-v1.0.0/bin/npm-hard",
+                    EntryType::Link,
+                    Some("node-v1.0.0/lib/node_modules/npm/bin/npm-cli.js"),
+                ),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(fs::read_to_string(root.join("bin/npm")).unwrap(), "pwned");
+        assert_eq!(fs::read_to_string(root.join("bin/npm-hard")).unwrap(), "pwned");
+    }
+
+    #[test]
+    fn test_zip_rejects_parent_dir_entries() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let archive = temp.path().join("crafted.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+        writer
+            .start_file("node-v1.0.0/../../evil.txt", zip::write::FileOptions::default())
+            .unwrap();
+        writer.write_all(b"pwned").unwrap();
+        writer.finish().unwrap();
+
+        let result = extract_archive(&archive, &temp.path().join("dest"));
+        assert!(result.is_err());
+        assert!(!temp.path().join("evil.txt").exists());
+    }
+
     #[test]
     fn test_move_extracted_files() {
         let temp_dir = std::env::temp_dir().join("nvm_test_move");
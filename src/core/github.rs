@@ -1,6 +1,7 @@
 // GitHub API functions for nvm self-update and releases
+use crate::config::Config;
 use crate::error::{message, with_context, Result};
-use reqwest;
+use crate::utils::http::create_client;
 use serde::Deserialize;
 use std::env;
 use std::path::Path;
@@ -25,16 +26,15 @@ pub struct GithubAsset {
 const GITHUB_REPO_NAME: &str = "nvm-rs";
 
 /// Obtiene la última release disponible en GitHub
-pub async fn get_latest_release() -> Result<GithubRelease> {
+pub async fn get_latest_release(config: &Config) -> Result<GithubRelease> {
     let url = format!(
         "https://api.github.com/repos/{}/{}/releases/latest",
         GITHUB_REPO_OWNER, GITHUB_REPO_NAME
     );
 
-    let client = reqwest::Client::new();
+    let client = create_client(config)?;
     let response = client
         .get(&url)
-        .header("User-Agent", "nvm-rs-installer")
         .send()
         .await
         .map_err(|e| with_context("Failed to fetch latest release", e))?;
@@ -55,16 +55,15 @@ pub async fn get_latest_release() -> Result<GithubRelease> {
 }
 
 /// Obtiene una release específica por tag
-pub async fn get_release_by_tag(tag: &str) -> Result<GithubRelease> {
+pub async fn get_release_by_tag(tag: &str, config: &Config) -> Result<GithubRelease> {
     let url = format!(
         "https://api.github.com/repos/{}/{}/releases/tags/{}",
         GITHUB_REPO_OWNER, GITHUB_REPO_NAME, tag
     );
 
-    let client = reqwest::Client::new();
+    let client = create_client(config)?;
     let response = client
         .get(&url)
-        .header("User-Agent", "nvm-rs-installer")
         .send()
         .await
         .map_err(|e| with_context("Failed to fetch release", e))?;
@@ -100,16 +99,15 @@ pub fn get_platform_asset_name(version: &str, with_self_update: bool) -> String
 }
 
 /// Descarga un asset desde GitHub
-pub async fn download_asset(asset: &GithubAsset, dest_path: &Path) -> Result<()> {
+pub async fn download_asset(asset: &GithubAsset, dest_path: &Path, config: &Config) -> Result<()> {
     use indicatif::{ProgressBar, ProgressStyle};
     use std::fs;
     use std::io::Write;
     use futures_util::StreamExt;
 
-    let client = reqwest::Client::new();
+    let client = create_client(config)?;
     let response = client
         .get(&asset.browser_download_url)
-        .header("User-Agent", "nvm-rs-installer")
         .send()
         .await
         .map_err(|e| with_context("Failed to download asset", e))?;
@@ -193,7 +193,11 @@ pub fn add_to_path(_install_dir: &Path) -> Result<()> {
         .to_path_buf();
 
     let (config_path, shell) = crate::platform::unix::shell::detect_shell_config()?;
-    let block = crate::platform::unix::shell::build_shell_block(&nvm_dir, shell);
+    let block = crate::platform::unix::shell::build_shell_block(
+        &nvm_dir,
+        shell,
+        crate::platform::unix::shell::ShellBlockOptions::configured(),
+    );
     crate::platform::unix::shell::ensure_shell_block(&config_path, &block)?;
     Ok(())
 }
@@ -201,8 +205,7 @@ pub fn add_to_path(_install_dir: &Path) -> Result<()> {
 #[cfg(not(windows))]
 #[allow(dead_code)]
 pub fn remove_from_path(_install_dir: &Path) -> Result<()> {
-    let (config_path, _) = crate::platform::unix::shell::detect_shell_config()?;
-    crate::platform::unix::shell::remove_shell_block(&config_path)?;
+    crate::platform::unix::shell::remove_shell_blocks()?;
     Ok(())
 }
 
@@ -211,7 +214,11 @@ pub fn remove_from_path(_install_dir: &Path) -> Result<()> {
 pub fn set_nvm_dir(_nvm_dir: &Path) -> Result<()> {
     let nvm_dir = _nvm_dir;
     let (config_path, shell) = crate::platform::unix::shell::detect_shell_config()?;
-    let block = crate::platform::unix::shell::build_shell_block(nvm_dir, shell);
+    let block = crate::platform::unix::shell::build_shell_block(
+        nvm_dir,
+        shell,
+        crate::platform::unix::shell::ShellBlockOptions::configured(),
+    );
     crate::platform::unix::shell::ensure_shell_block(&config_path, &block)?;
     Ok(())
 }
@@ -219,8 +226,7 @@ pub fn set_nvm_dir(_nvm_dir: &Path) -> Result<()> {
 #[cfg(not(windows))]
 #[allow(dead_code)]
 pub fn remove_nvm_dir() -> Result<()> {
-    let (config_path, _) = crate::platform::unix::shell::detect_shell_config()?;
-    crate::platform::unix::shell::remove_shell_block(&config_path)?;
+    crate::platform::unix::shell::remove_shell_blocks()?;
     Ok(())
 }
 
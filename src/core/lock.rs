@@ -0,0 +1,135 @@
+// Advisory lock that serializes changes to NVM_HOME across processes
+use crate::config::Config;
+use crate::error::{message, with_context, Result};
+use crate::t;
+use fs2::FileExt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const LOCK_FILE: &str = ".nvm.lock";
+const CACHE_LOCK_FILE: &str = ".archives.lock";
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Lock exclusivo sobre $NVM_HOME; se libera al salir de scope
+/// Se toma en install, uninstall, use, cleanup y al escribir aliases
+#[derive(Debug)]
+pub struct NvmLock {
+    file: File,
+}
+
+impl NvmLock {
+    /// Espera hasta `config.lock_timeout_secs` a que otro proceso de nvm termine
+    pub fn acquire(config: &Config) -> Result<Self> {
+        Self::acquire_at(
+            &config.nvm_dir.join(LOCK_FILE),
+            Duration::from_secs(config.lock_timeout_secs),
+            "lock_waiting",
+        )
+    }
+
+    /// Lock del caché de archivos (`Config::cache_dir`), que pueden compartir varios $NVM_HOME
+    pub fn acquire_cache(config: &Config) -> Result<Self> {
+        Self::acquire_at(
+            &config.cache_dir.join(CACHE_LOCK_FILE),
+            Duration::from_secs(config.lock_timeout_secs),
+            "lock_waiting_cache",
+        )
+    }
+
+    fn acquire_at(path: &Path, timeout: Duration, waiting_key: &str) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .map_err(|e| with_context(&format!("Failed to open lock file: {}", path.display()), e))?;
+
+        if file.try_lock_exclusive().is_err() {
+            let holder = read_holder(&mut file);
+            eprintln!(
+                "{}",
+                t!(waiting_key).replace("{pid}", holder.as_deref().unwrap_or("?"))
+            );
+
+            let start = Instant::now();
+            loop {
+                std::thread::sleep(POLL_INTERVAL);
+                if file.try_lock_exclusive().is_ok() {
+                    break;
+                }
+                if start.elapsed() >= timeout {
+                    return Err(message(format!(
+                        "Timed out after {}s waiting for another nvm process (pid {}) to release {}",
+                        timeout.as_secs(),
+                        holder.as_deref().unwrap_or("?"),
+                        path.display()
+                    )));
+                }
+            }
+        }
+
+        // Registrar el PID del dueño para los mensajes de espera de otros procesos
+        let _ = file.set_len(0);
+        let _ = file.seek(SeekFrom::Start(0));
+        let _ = write!(file, "{}", std::process::id());
+        let _ = file.flush();
+
+        Ok(Self { file })
+    }
+}
+
+impl Drop for NvmLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+fn read_holder(file: &mut File) -> Option<String> {
+    let mut content = String::new();
+    file.seek(SeekFrom::Start(0)).ok()?;
+    file.read_to_string(&mut content).ok()?;
+    let pid = content.trim();
+    (!pid.is_empty()).then(|| pid.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lock_times_out_while_held() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(LOCK_FILE);
+
+        let held = NvmLock::acquire_at(&path, Duration::from_secs(1), "lock_waiting").unwrap();
+        let err = NvmLock::acquire_at(&path, Duration::from_millis(300), "lock_waiting").unwrap_err();
+        assert!(err.to_string().contains("Timed out"));
+        assert!(err.to_string().contains(&std::process::id().to_string()));
+
+        drop(held);
+        assert!(NvmLock::acquire_at(&path, Duration::from_millis(300), "lock_waiting").is_ok());
+    }
+
+    #[test]
+    fn test_waiter_gets_lock_after_release() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(LOCK_FILE);
+
+        let held = NvmLock::acquire_at(&path, Duration::from_secs(1), "lock_waiting").unwrap();
+        let waiter_path = path.clone();
+        let waiter =
+            std::thread::spawn(move || NvmLock::acquire_at(&waiter_path, Duration::from_secs(10), "lock_waiting").is_ok());
+
+        std::thread::sleep(Duration::from_millis(400));
+        drop(held);
+        assert!(waiter.join().unwrap());
+    }
+}
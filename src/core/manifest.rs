@@ -0,0 +1,213 @@
+// Per-version install manifest: where an installed version came from and when
+use crate::error::{with_context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Archivo escrito dentro de cada versions/<versión>
+pub const MANIFEST_FILE: &str = ".nvm-manifest.json";
+
+/// Cómo se obtuvo la versión
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallSource {
+    /// Binario oficial descargado de NODE_MIRROR
+    Mirror,
+    /// `nvm install --from-archive`
+    Archive,
+    /// `nvm install --from-url`
+    Url,
+    /// `nvm install --source`
+    Source,
+}
+
+impl InstallSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InstallSource::Mirror => "mirror",
+            InstallSource::Archive => "archive",
+            InstallSource::Url => "url",
+            InstallSource::Source => "source",
+        }
+    }
+}
+
+/// Origen conocido antes de instalar; la versión se conoce después de extraer
+#[derive(Debug, Clone)]
+pub struct InstallOrigin {
+    pub source: InstallSource,
+    /// URL o ruta local del archivo
+    pub location: String,
+    /// SHA256 verificado del archivo (None si no se verificó)
+    pub sha256: Option<String>,
+}
+
+impl InstallOrigin {
+    pub fn new(source: InstallSource, location: impl Into<String>, sha256: Option<&str>) -> Self {
+        InstallOrigin {
+            source,
+            location: location.into(),
+            sha256: sha256.map(str::to_string),
+        }
+    }
+}
+
+/// Contenido de .nvm-manifest.json
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstallManifest {
+    pub version: String,
+    pub source: InstallSource,
+    /// URL o ruta local del archivo instalado
+    pub location: String,
+    /// Nombre del archivo (ej: node-v20.11.0-linux-x64.tar.xz)
+    pub archive: Option<String>,
+    /// SHA256 verificado del archivo
+    pub sha256: Option<String>,
+    /// Plataforma del binario (ej: linux-x64) o "source" si se compiló
+    pub distribution: Option<String>,
+    /// Flags de ./configure usados al compilar
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub configure_flags: Vec<String>,
+    /// Segundos desde UNIX_EPOCH
+    pub installed_at: u64,
+    /// Versión de nvm-rs que hizo la instalación
+    pub nvm_version: String,
+}
+
+impl InstallManifest {
+    pub fn new(version: &str, origin: &InstallOrigin) -> Self {
+        // Último segmento de la ruta o URL, sin query string
+        let archive = origin
+            .location
+            .split(['?', '#'])
+            .next()
+            .and_then(|path| path.rsplit(['/', '\\']).next())
+            .filter(|name| !name.is_empty())
+            .map(str::to_string);
+
+        let distribution = if origin.source == InstallSource::Source {
+            Some("source".to_string())
+        } else {
+            archive
+                .as_deref()
+                .and_then(|name| distribution_from_archive(name, version))
+        };
+
+        InstallManifest {
+            version: version.to_string(),
+            source: origin.source,
+            location: origin.location.clone(),
+            archive,
+            sha256: origin.sha256.clone(),
+            distribution,
+            configure_flags: Vec::new(),
+            installed_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            nvm_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    /// Escribe el manifiesto en el directorio de la versión
+    pub fn write(&self, version_dir: &Path) -> Result<()> {
+        let path = version_dir.join(MANIFEST_FILE);
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(&path, content)
+            .map_err(|e| with_context(&format!("Failed to write {}", path.display()), e))
+    }
+
+    /// Lee el manifiesto; `None` para versiones instaladas antes de que existiera
+    /// o cuyo manifiesto está dañado
+    pub fn read(version_dir: &Path) -> Option<Self> {
+        let content = fs::read_to_string(version_dir.join(MANIFEST_FILE)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Fecha de instalación como YYYY-MM-DD (UTC)
+    pub fn installed_date(&self) -> String {
+        format_date(self.installed_at)
+    }
+}
+
+/// "node-v20.11.0-linux-x64.tar.xz" → "linux-x64"
+fn distribution_from_archive(archive: &str, version: &str) -> Option<String> {
+    let rest = archive.strip_prefix(&format!("node-{}-", version))?;
+    let platform = [".tar.gz", ".tar.xz", ".tgz", ".txz", ".zip"]
+        .iter()
+        .find_map(|ext| rest.strip_suffix(ext))
+        .unwrap_or(rest);
+    (!platform.is_empty()).then(|| platform.to_string())
+}
+
+/// Convierte segundos desde UNIX_EPOCH a YYYY-MM-DD (UTC)
+pub fn format_date(secs: u64) -> String {
+    // Algoritmo civil_from_days de Howard Hinnant
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let temp = TempDir::new().unwrap();
+        let origin = InstallOrigin::new(
+            InstallSource::Mirror,
+            "https://nodejs.org/dist/v20.11.0/node-v20.11.0-linux-x64.tar.xz",
+            Some("abc123"),
+        );
+
+        let manifest = InstallManifest::new("v20.11.0", &origin);
+        assert_eq!(manifest.archive.as_deref(), Some("node-v20.11.0-linux-x64.tar.xz"));
+        assert_eq!(manifest.distribution.as_deref(), Some("linux-x64"));
+        assert_eq!(manifest.nvm_version, env!("CARGO_PKG_VERSION"));
+
+        manifest.write(temp.path()).unwrap();
+        let content = fs::read_to_string(temp.path().join(MANIFEST_FILE)).unwrap();
+        assert!(content.contains(r#""source": "mirror""#));
+        assert_eq!(InstallManifest::read(temp.path()), Some(manifest));
+    }
+
+    #[test]
+    fn test_manifest_missing_or_damaged() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(InstallManifest::read(temp.path()), None);
+
+        fs::write(temp.path().join(MANIFEST_FILE), "{ not json").unwrap();
+        assert_eq!(InstallManifest::read(temp.path()), None);
+    }
+
+    #[test]
+    fn test_distribution_from_archive() {
+        assert_eq!(
+            distribution_from_archive("node-v20.11.0-win-x64.zip", "v20.11.0").as_deref(),
+            Some("win-x64")
+        );
+        assert_eq!(distribution_from_archive("custom.tar.gz", "v20.11.0"), None);
+
+        let source = InstallOrigin::new(InstallSource::Source, "/tmp/node-v20.11.0.tar.gz", None);
+        let manifest = InstallManifest::new("v20.11.0", &source);
+        assert_eq!(manifest.distribution.as_deref(), Some("source"));
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_707_868_800), "2024-02-14");
+    }
+}
@@ -7,5 +7,13 @@
 pub mod installer;
 pub mod detection;
 pub mod github;
+pub mod archive_cache;
+pub mod lock;
+pub mod build;
+pub mod npm;
+pub mod manifest;
+pub mod dedupe;
+pub mod shims;
+pub mod state;
 
 pub use cache::*;
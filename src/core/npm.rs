@@ -0,0 +1,392 @@
+// Helpers to run the npm and corepack bundled with an installed Node.js version
+use crate::config::{Config, NpmCacheMode};
+use crate::error::{message, with_context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Directorio con los ejecutables de una versión (node, npm, npx)
+pub fn bin_dir(version_dir: &Path) -> PathBuf {
+    #[cfg(windows)]
+    {
+        version_dir.to_path_buf()
+    }
+
+    #[cfg(not(windows))]
+    {
+        version_dir.join("bin")
+    }
+}
+
+/// Variables de npm que nvm fija al activar una versión
+pub const NPM_PREFIX_VAR: &str = "npm_config_prefix";
+pub const NPM_CACHE_VAR: &str = "npm_config_cache";
+
+/// Entorno de npm para una versión: `npm_config_prefix` apunta a la versión (sus paquetes
+/// globales quedan en ella y sus binarios en su `bin`) y `npm_config_cache` según `npm_cache`
+pub fn isolation_env(version_dir: &Path, config: &Config) -> Vec<(&'static str, PathBuf)> {
+    let mut vars = Vec::new();
+    if config.npm_prefix_isolation {
+        vars.push((NPM_PREFIX_VAR, version_dir.to_path_buf()));
+    }
+    match config.npm_cache {
+        NpmCacheMode::Default => {}
+        NpmCacheMode::Shared => vars.push((NPM_CACHE_VAR, config.npm_cache_dir())),
+        NpmCacheMode::PerVersion => vars.push((NPM_CACHE_VAR, version_dir.join(".npm-cache"))),
+    }
+    vars
+}
+
+/// Variables de `isolation_env`; se quitan al pasar al Node.js del sistema
+pub fn isolation_vars(config: &Config) -> Vec<&'static str> {
+    let mut vars = Vec::new();
+    if config.npm_prefix_isolation {
+        vars.push(NPM_PREFIX_VAR);
+    }
+    if config.npm_cache != NpmCacheMode::Default {
+        vars.push(NPM_CACHE_VAR);
+    }
+    vars
+}
+
+/// `prefix` del .npmrc del usuario ($NPM_CONFIG_USERCONFIG o ~/.npmrc)
+/// Fuera del entorno de nvm (link global sin `nvm env`) hace que los globales de todas las
+/// versiones vayan al mismo directorio
+pub fn user_npmrc_prefix() -> Option<(PathBuf, String)> {
+    let path = std::env::var_os("NPM_CONFIG_USERCONFIG")
+        .or_else(|| std::env::var_os("npm_config_userconfig"))
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".npmrc")))?;
+    let content = fs::read_to_string(&path).ok()?;
+    npmrc_prefix(&content).map(|prefix| (path, prefix))
+}
+
+/// Valor de `prefix` en un .npmrc (formato ini; `;` y `#` inician comentarios)
+fn npmrc_prefix(content: &str) -> Option<String> {
+    content.lines().rev().find_map(|line| {
+        let line = line.trim();
+        if line.starts_with(';') || line.starts_with('#') {
+            return None;
+        }
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "prefix").then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Comando para una herramienta de la versión (npm, corepack...) con su `bin` primero en PATH
+/// Los scripts de npm usan `#!/usr/bin/env node`, así que el PATH decide qué node se ejecuta.
+/// Los paquetes globales que instala nvm siempre van a la versión, aunque el .npmrc diga otra cosa
+pub fn tool_command(version_dir: &Path, tool: &str) -> Command {
+    let bin = bin_dir(version_dir);
+
+    #[cfg(windows)]
+    let program = bin.join(format!("{}.cmd", tool));
+    #[cfg(not(windows))]
+    let program = bin.join(tool);
+
+    let mut paths = vec![bin];
+    if let Some(current) = std::env::var_os("PATH") {
+        paths.extend(std::env::split_paths(&current));
+    }
+
+    let mut command = Command::new(program);
+    if let Ok(path) = std::env::join_paths(paths) {
+        command.env("PATH", path);
+    }
+    command.env(NPM_PREFIX_VAR, version_dir);
+    command
+}
+
+/// Paquetes incluidos con Node.js; nunca se reinstalan desde otra versión
+pub const BUNDLED_PACKAGES: &[&str] = &["npm", "corepack"];
+
+/// Paquete instalado globalmente en una versión
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobalPackage {
+    pub name: String,
+    pub version: Option<String>,
+    /// Enlazado con `npm link` (apunta a un directorio local)
+    pub linked: bool,
+}
+
+impl GlobalPackage {
+    /// Especificación para `npm install`: `nombre` o `nombre@versión` si se conserva la versión
+    pub fn spec(&self, keep_version: bool) -> String {
+        match (&self.version, keep_version) {
+            (Some(version), true) => format!("{}@{}", self.name, version),
+            _ => self.name.clone(),
+        }
+    }
+}
+
+/// Directorio de paquetes globales de una versión
+pub fn global_modules_dir(version_dir: &Path) -> PathBuf {
+    #[cfg(windows)]
+    {
+        version_dir.join("node_modules")
+    }
+
+    #[cfg(not(windows))]
+    {
+        version_dir.join("lib").join("node_modules")
+    }
+}
+
+/// Lista los paquetes globales leyendo node_modules directamente (no requiere ejecutar npm)
+/// Incluye paquetes con scope (@scope/nombre); el resultado está ordenado por nombre
+pub fn list_global_packages(version_dir: &Path) -> Result<Vec<GlobalPackage>> {
+    let modules_dir = global_modules_dir(version_dir);
+    if !modules_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut packages = Vec::new();
+    for entry in fs::read_dir(&modules_dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+
+        if name.starts_with('@') {
+            for scoped in fs::read_dir(entry.path())? {
+                let scoped = scoped?;
+                let scoped_name = format!("{}/{}", name, scoped.file_name().to_string_lossy());
+                packages.push(read_package(&scoped.path(), scoped_name));
+            }
+        } else {
+            packages.push(read_package(&entry.path(), name));
+        }
+    }
+
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(packages)
+}
+
+fn read_package(path: &Path, name: String) -> GlobalPackage {
+    let linked = fs::symlink_metadata(path)
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false);
+
+    let version = fs::read_to_string(path.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|json| json.get("version")?.as_str().map(str::to_string));
+
+    GlobalPackage {
+        name,
+        version,
+        linked,
+    }
+}
+
+/// Lee $NVM_HOME/default-packages: un paquete por línea, `#` inicia un comentario
+pub fn read_default_packages(path: &Path) -> Result<Vec<String>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path)
+        .map_err(|e| with_context(&format!("Failed to read {}", path.display()), e))?;
+
+    Ok(content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .flat_map(str::split_whitespace)
+        .map(str::to_string)
+        .collect())
+}
+
+/// Instala un paquete global con el npm de la versión
+pub fn install_global(version_dir: &Path, package: &str) -> Result<()> {
+    let output = tool_command(version_dir, "npm")
+        .args(["install", "--global", "--no-fund", "--no-audit", package])
+        .output()
+        .map_err(|e| with_context("Failed to run npm", e))?;
+
+    check_output(output)
+}
+
+/// Indica si la versión incluye corepack (Node.js >= 16.9)
+pub fn has_corepack(version_dir: &Path) -> bool {
+    let bin = bin_dir(version_dir);
+    #[cfg(windows)]
+    let corepack = bin.join("corepack.cmd");
+    #[cfg(not(windows))]
+    let corepack = bin.join("corepack");
+    corepack.exists()
+}
+
+/// `corepack enable`: crea los shims de pnpm/yarn junto al node de la versión
+pub fn corepack_enable(version_dir: &Path) -> Result<()> {
+    let output = tool_command(version_dir, "corepack")
+        .arg("enable")
+        .output()
+        .map_err(|e| with_context("Failed to run corepack", e))?;
+
+    check_output(output)
+}
+
+/// `corepack prepare <spec> --activate`: descarga el gestor de paquetes por adelantado
+pub fn corepack_prepare(version_dir: &Path, spec: &str) -> Result<()> {
+    let output = tool_command(version_dir, "corepack")
+        .args(["prepare", spec, "--activate"])
+        .output()
+        .map_err(|e| with_context("Failed to run corepack", e))?;
+
+    check_output(output)
+}
+
+/// Busca hacia arriba desde `start` el package.json más cercano que declare `packageManager`
+/// Devuelve la ruta del package.json y el valor (ej: "pnpm@9.1.0")
+pub fn find_package_manager(start: &Path) -> Option<(PathBuf, String)> {
+    let mut dir = start.to_path_buf();
+    loop {
+        let manifest = dir.join("package.json");
+        if let Some(spec) = fs::read_to_string(&manifest)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|json| json.get("packageManager")?.as_str().map(str::to_string))
+        {
+            return Some((manifest, spec));
+        }
+
+        if !dir.pop() {
+            return None;
+        }
+    }
+}
+
+/// Devuelve un error con la última línea relevante de stderr si el comando falló
+pub fn check_output(output: Output) -> Result<()> {
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let reason = stderr
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("unknown error")
+        .to_string();
+
+    Err(message(format!("{} ({})", reason, output.status)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_isolation_env() {
+        let temp = TempDir::new().unwrap();
+        let mut config = Config::for_tests(temp.path());
+        let v20 = config.versions_dir().join("v20.11.0");
+
+        assert_eq!(isolation_env(&v20, &config), [(NPM_PREFIX_VAR, v20.clone())]);
+
+        config.npm_cache = NpmCacheMode::PerVersion;
+        assert_eq!(isolation_env(&v20, &config)[1], (NPM_CACHE_VAR, v20.join(".npm-cache")));
+
+        config.npm_prefix_isolation = false;
+        config.npm_cache = NpmCacheMode::Shared;
+        assert_eq!(isolation_env(&v20, &config), [(NPM_CACHE_VAR, temp.path().join("npm-cache"))]);
+        assert_eq!(isolation_vars(&config), [NPM_CACHE_VAR]);
+    }
+
+    #[test]
+    fn test_npmrc_prefix() {
+        assert_eq!(npmrc_prefix("registry=https://r.local/\nprefix = /usr/local\n"), Some("/usr/local".to_string()));
+        assert_eq!(npmrc_prefix("; prefix=/usr/local\n# prefix=/opt\n"), None);
+        // npm usa la última definición
+        assert_eq!(npmrc_prefix("prefix=/a\nprefix=\"/b\"\n"), Some("/b".to_string()));
+        assert_eq!(npmrc_prefix("cache-prefix=/x\n"), None);
+    }
+
+    #[test]
+    fn test_read_default_packages() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("default-packages");
+        fs::write(
+            &path,
+            "# herramientas del equipo\npnpm\ntypescript@5.4 # compilador\n\n  eslint  prettier\n",
+        )
+        .unwrap();
+
+        let packages = read_default_packages(&path).unwrap();
+        assert_eq!(packages, vec!["pnpm", "typescript@5.4", "eslint", "prettier"]);
+
+        assert!(read_default_packages(&temp.path().join("missing")).unwrap().is_empty());
+    }
+
+    fn write_package(modules: &Path, name: &str, version: &str) {
+        let dir = modules.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("package.json"),
+            format!(r#"{{"name": "{}", "version": "{}"}}"#, name, version),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_list_global_packages() {
+        let temp = TempDir::new().unwrap();
+        let modules = global_modules_dir(temp.path());
+        write_package(&modules, "npm", "10.2.4");
+        write_package(&modules, "typescript", "5.4.2");
+        write_package(&modules, "@angular/cli", "17.0.0");
+        fs::create_dir_all(modules.join(".bin")).unwrap();
+
+        let packages = list_global_packages(temp.path()).unwrap();
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["@angular/cli", "npm", "typescript"]);
+
+        let typescript = &packages[2];
+        assert_eq!(typescript.spec(false), "typescript");
+        assert_eq!(typescript.spec(true), "typescript@5.4.2");
+        assert!(!typescript.linked);
+    }
+
+    #[test]
+    fn test_find_package_manager() {
+        let temp = TempDir::new().unwrap();
+        let workspace = temp.path().join("packages").join("web");
+        fs::create_dir_all(&workspace).unwrap();
+        fs::write(
+            temp.path().join("package.json"),
+            r#"{"name": "monorepo", "packageManager": "pnpm@9.1.0"}"#,
+        )
+        .unwrap();
+        // Los paquetes del workspace no declaran packageManager
+        fs::write(workspace.join("package.json"), r#"{"name": "web"}"#).unwrap();
+
+        let (manifest, spec) = find_package_manager(&workspace).unwrap();
+        assert_eq!(manifest, temp.path().join("package.json"));
+        assert_eq!(spec, "pnpm@9.1.0");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_install_global_reports_failures() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let bin = temp.path().join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        let npm = bin.join("npm");
+        fs::write(
+            &npm,
+            "#!/bin/sh\nfor last; do :; done\n[ \"$last\" = bad ] && { echo \"npm ERR! 404 'bad' is not in the registry\" >&2; exit 1; }\nexit 0\n",
+        )
+        .unwrap();
+        fs::set_permissions(&npm, fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert!(install_global(temp.path(), "pnpm").is_ok());
+        let err = install_global(temp.path(), "bad").unwrap_err();
+        assert!(err.to_string().contains("not in the registry"));
+    }
+}
@@ -0,0 +1,145 @@
+// Shims: small scripts in $NVM_HOME/shims that run the version resolved at exec time
+use crate::error::{with_context, Result};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Herramientas que siempre tienen shim, aunque la versión no las incluya (ej: corepack)
+pub const SHIM_TOOLS: [&str; 4] = ["node", "npm", "npx", "corepack"];
+
+/// Nombres con shim: las herramientas de Node.js y los ejecutables globales de cada versión
+pub fn shim_names(version_dirs: &[PathBuf]) -> BTreeSet<String> {
+    let mut names: BTreeSet<String> = SHIM_TOOLS.iter().map(|tool| tool.to_string()).collect();
+
+    for version_dir in version_dirs {
+        let Ok(entries) = fs::read_dir(super::npm::bin_dir(version_dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if let Some(name) = executable_name(&entry.path()) {
+                names.insert(name);
+            }
+        }
+    }
+
+    names
+}
+
+/// Escribe un shim por nombre y elimina los que ya no corresponden a ningún ejecutable
+/// Los shims llaman a `<nvm> shim <nombre>`, que resuelve la versión en cada ejecución
+pub fn write_shims(shims_dir: &Path, nvm_exe: &Path, names: &BTreeSet<String>) -> Result<usize> {
+    fs::create_dir_all(shims_dir)
+        .map_err(|e| with_context("Failed to create shims directory", e))?;
+
+    for entry in fs::read_dir(shims_dir)?.flatten() {
+        let path = entry.path();
+        let stale = executable_name(&path).is_none_or(|name| !names.contains(&name));
+        if stale && path.is_file() {
+            fs::remove_file(&path)
+                .map_err(|e| with_context(&format!("Failed to remove {}", path.display()), e))?;
+        }
+    }
+
+    for name in names {
+        let path = shim_path(shims_dir, name);
+        fs::write(&path, shim_script(nvm_exe, name))
+            .map_err(|e| with_context(&format!("Failed to write {}", path.display()), e))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+        }
+    }
+
+    Ok(names.len())
+}
+
+/// Ejecutable `name` dentro del directorio `bin` de una versión
+pub fn find_tool(bin_dir: &Path, name: &str) -> Option<PathBuf> {
+    #[cfg(windows)]
+    let candidates = vec![bin_dir.join(format!("{}.exe", name)), bin_dir.join(format!("{}.cmd", name))];
+    #[cfg(not(windows))]
+    let candidates = vec![bin_dir.join(name)];
+
+    candidates.into_iter().find(|path| path.is_file())
+}
+
+/// Nombre del ejecutable, o `None` si no es un ejecutable o el nombre no es seguro para un script
+fn executable_name(path: &Path) -> Option<String> {
+    #[cfg(windows)]
+    let name = {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        if extension != "exe" && extension != "cmd" {
+            return None;
+        }
+        path.file_stem()?.to_str()?
+    };
+    #[cfg(not(windows))]
+    let name = path.file_name()?.to_str()?;
+
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '@' | '+'));
+    valid.then(|| name.to_string())
+}
+
+#[cfg(windows)]
+fn shim_path(shims_dir: &Path, name: &str) -> PathBuf {
+    shims_dir.join(format!("{}.cmd", name))
+}
+
+#[cfg(not(windows))]
+fn shim_path(shims_dir: &Path, name: &str) -> PathBuf {
+    shims_dir.join(name)
+}
+
+#[cfg(windows)]
+fn shim_script(nvm_exe: &Path, name: &str) -> String {
+    format!("@\"{}\" shim {} %*\r\n", nvm_exe.display(), name)
+}
+
+#[cfg(not(windows))]
+fn shim_script(nvm_exe: &Path, name: &str) -> String {
+    let exe = nvm_exe.to_string_lossy().replace('\'', "'\\''");
+    format!("#!/bin/sh\nexec '{}' shim {} \"$@\"\n", exe, name)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_shims_for_tools_and_global_bins() {
+        let temp = TempDir::new().unwrap();
+        let v20 = temp.path().join("versions").join("v20.11.0");
+        fs::create_dir_all(v20.join("bin")).unwrap();
+        for bin in ["node", "npm", "npx", "tsc", ".hidden", "bad name"] {
+            fs::write(v20.join("bin").join(bin), "").unwrap();
+        }
+
+        let names = shim_names(&[v20]);
+        assert_eq!(
+            names.iter().map(String::as_str).collect::<Vec<_>>(),
+            ["corepack", "node", "npm", "npx", "tsc"]
+        );
+
+        let shims = temp.path().join("shims");
+        fs::create_dir_all(&shims).unwrap();
+        fs::write(shims.join("eslint"), "").unwrap();
+
+        let written = write_shims(&shims, Path::new("/opt/nvm's/bin/nvm"), &names).unwrap();
+        assert_eq!(written, 5);
+        assert!(!shims.join("eslint").exists());
+
+        let script = fs::read_to_string(shims.join("tsc")).unwrap();
+        assert_eq!(script, "#!/bin/sh\nexec '/opt/nvm'\\''s/bin/nvm' shim tsc \"$@\"\n");
+
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(shims.join("node")).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+    }
+}
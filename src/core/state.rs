@@ -0,0 +1,125 @@
+// Active-version state persisted in $NVM_HOME/state.json
+use crate::config::Config;
+use crate::error::{with_context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Archivo de estado en $NVM_HOME
+pub const STATE_FILE: &str = "state.json";
+
+/// Versión activada con `nvm use` y cuándo (segundos desde epoch)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Activation {
+    pub version: String,
+    pub activated_at: u64,
+}
+
+/// Versión global actual y la anterior (para `nvm use -`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NvmState {
+    pub current: Option<Activation>,
+    pub previous: Option<Activation>,
+}
+
+impl NvmState {
+    pub fn path(config: &Config) -> PathBuf {
+        config.nvm_dir.join(STATE_FILE)
+    }
+
+    /// Lee el estado; un archivo ausente o dañado equivale a un estado vacío
+    pub fn load(config: &Config) -> Self {
+        fs::read_to_string(Self::path(config))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Escribe el estado de forma atómica (archivo temporal + rename)
+    pub fn save(&self, config: &Config) -> Result<()> {
+        let path = Self::path(config);
+        let temp = path.with_extension("json.tmp");
+        let content = serde_json::to_string_pretty(self)?;
+
+        fs::write(&temp, content)
+            .map_err(|e| with_context(&format!("Failed to write {}", temp.display()), e))?;
+        fs::rename(&temp, &path)
+            .map_err(|e| with_context(&format!("Failed to write {}", path.display()), e))
+    }
+
+    /// Registra `version` como actual; la actual pasa a ser la anterior si es distinta
+    pub fn activate(&mut self, version: &str) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        if let Some(current) = self.current.take() {
+            if current.version != version {
+                self.previous = Some(current);
+            }
+        }
+        self.current = Some(Activation {
+            version: version.to_string(),
+            activated_at: now,
+        });
+    }
+
+    /// Olvida una versión desinstalada
+    pub fn forget(&mut self, version: &str) {
+        if self.current.as_ref().is_some_and(|a| a.version == version) {
+            self.current = None;
+        }
+        if self.previous.as_ref().is_some_and(|a| a.version == version) {
+            self.previous = None;
+        }
+    }
+
+    pub fn current_version(&self) -> Option<&str> {
+        self.current.as_ref().map(|a| a.version.as_str())
+    }
+
+    pub fn previous_version(&self) -> Option<&str> {
+        self.previous.as_ref().map(|a| a.version.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_activate_tracks_previous_version() {
+        let temp = TempDir::new().unwrap();
+        let config = Config::for_tests(temp.path());
+        assert_eq!(NvmState::load(&config), NvmState::default());
+
+        let mut state = NvmState::default();
+        state.activate("v18.19.0");
+        state.activate("v20.11.0");
+        // Repetir la versión actual no pierde la anterior
+        state.activate("v20.11.0");
+        state.save(&config).unwrap();
+
+        let state = NvmState::load(&config);
+        assert_eq!(state.current_version(), Some("v20.11.0"));
+        assert_eq!(state.previous_version(), Some("v18.19.0"));
+        assert!(state.current.as_ref().unwrap().activated_at > 0);
+
+        let mut state = state;
+        state.forget("v18.19.0");
+        assert_eq!(state.previous_version(), None);
+        assert_eq!(state.current_version(), Some("v20.11.0"));
+    }
+
+    #[test]
+    fn test_damaged_state_is_empty() {
+        let temp = TempDir::new().unwrap();
+        let config = Config::for_tests(temp.path());
+        fs::write(NvmState::path(&config), "{ not json").unwrap();
+        assert_eq!(NvmState::load(&config), NvmState::default());
+    }
+}
@@ -55,49 +55,6 @@ pub fn create_or_update_symlink(target: &Path, link: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Persiste la versión actual en un archivo .nvm-version para recuperación confiable
-pub fn persist_current_version(link: &Path, version: &str) -> Result<()> {
-    // Crear directorio 'current' si no existe
-    if let Some(parent) = link.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| with_context("Failed to create current directory", e))?;
-    }
-
-    let version_file = link.join(".nvm-version");
-    std::fs::write(&version_file, version).map_err(|e| {
-        with_context(
-            &format!("Failed to persist version to {}", version_file.display()),
-            e,
-        )
-    })?;
-
-    Ok(())
-}
-
-/// Lee la versión persistida desde archivo .nvm-version
-#[allow(dead_code)] // Will be used in Phase 2 (version detection improvements)
-pub fn read_persisted_version(link: &Path) -> Result<Option<String>> {
-    let version_file = link.join(".nvm-version");
-
-    if !version_file.exists() {
-        return Ok(None);
-    }
-
-    let content = std::fs::read_to_string(&version_file).map_err(|e| {
-        with_context(
-            &format!("Failed to read persisted version from {}", version_file.display()),
-            e,
-        )
-    })?;
-
-    let version = content.trim().to_string();
-    if version.is_empty() {
-        Ok(None)
-    } else {
-        Ok(Some(version))
-    }
-}
-
 /// Elimina un symlink/junction existente
 pub fn remove_symlink(link: &Path) -> Result<()> {
     if !link.exists() && link.symlink_metadata().is_err() {
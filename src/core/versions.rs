@@ -58,15 +58,29 @@ pub fn name(&self) -> Option<&str> {
 /// Resuelve un alias o versión a una versión completa
 #[allow(dead_code)] // Will be used in Phase 2-3 (ls-remote, install)
 pub fn resolve_version(version: &str, available_versions: &[NodeVersion]) -> Result<String> {
+    let aliases = super::aliases::Aliases::load().unwrap_or_default();
+    resolve_version_with(version, available_versions, &aliases)
+}
+
+/// `resolve_version` con los aliases ya cargados
+fn resolve_version_with(
+    version: &str,
+    available_versions: &[NodeVersion],
+    aliases: &super::aliases::Aliases,
+) -> Result<String> {
     // Primero, intentar resolver como alias personalizado
-    if let Ok(aliases) = super::aliases::Aliases::load() {
-        if let Some(resolved) = aliases.resolve(version) {
-            // El alias puede apuntar a otro alias o versión, continuar resolución
-            if resolved != version {
-                // Evitar recursión infinita
-                return resolve_version(&resolved, available_versions);
-            }
+    // El alias puede apuntar a otro alias, a una versión o a un spec (`lts/*`, `^20`)
+    let chain = aliases.chain(version);
+    if let Some(target) = chain.last().filter(|_| chain.len() > 1) {
+        // Evitar recursión infinita con aliases que se apuntan entre sí
+        if aliases.contains(target) {
+            return Err(message(format!(
+                "Alias cycle: {} -> {}",
+                chain.join(" -> "),
+                aliases.get(target).map(String::as_str).unwrap_or_default()
+            )));
         }
+        return resolve_version_with(target, available_versions, aliases);
     }
 
     // Si ya es una versión completa, normalizarla
@@ -88,7 +102,7 @@ pub fn resolve_version(version: &str, available_versions: &[NodeVersion]) -> Res
             }
             Err(message("No versions available"))
         }
-        "lts" => {
+        "lts" | "lts/*" => {
             if let Some(lts_version) = available_versions.iter().find(|v| v.lts.is_lts()) {
                 return Ok(lts_version.version.clone());
             }
@@ -119,11 +133,127 @@ pub fn resolve_version(version: &str, available_versions: &[NodeVersion]) -> Res
                 return Ok(lts_version.version.clone());
             }
 
+            // Spec de versión ("20", "20.x", "^20.5", "~20.11"): la más nueva que lo cumple
+            if let Some(matching) = available_versions
+                .iter()
+                .find(|v| matches_spec(version, &v.version, v.lts.name()))
+            {
+                return Ok(matching.version.clone());
+            }
+
             Err(message(format!("Unknown version or alias: {}", version)))
         }
     }
 }
 
+/// Busca una versión instalada: exacta ("v20.11.0", "20.11.0") o parcial ("20", "v20.11")
+/// Para versiones parciales devuelve la más reciente que coincida
+pub fn find_installed_version(spec: &str, installed: &[String]) -> Option<String> {
+    let normalized = normalize_version(spec.trim());
+    if installed.contains(&normalized) {
+        return Some(normalized);
+    }
+
+    let prefix = format!("{}.", normalized);
+    let mut matches: Vec<String> = installed
+        .iter()
+        .filter(|v| v.starts_with(&prefix))
+        .cloned()
+        .collect();
+    sort_versions(&mut matches);
+    matches.into_iter().next()
+}
+
+/// Indica si `version` cumple el spec de un alias que sigue versiones: `latest`, `lts`, `lts/*`,
+/// `lts/<nombre>`, una versión parcial (`20`, `20.x`, `v20.11`) o un rango `^20.5` / `~20.11`
+pub fn matches_spec(spec: &str, version: &str, lts_name: Option<&str>) -> bool {
+    let spec = spec.trim().to_lowercase();
+    match spec.as_str() {
+        "latest" | "node" | "*" => return true,
+        "lts" | "lts/*" => return lts_name.is_some(),
+        _ => {}
+    }
+    if let Some(name) = spec.strip_prefix("lts/") {
+        return lts_name.is_some_and(|lts| lts.eq_ignore_ascii_case(name));
+    }
+
+    let (operator, rest) = match spec.strip_prefix('^') {
+        Some(rest) => (Some('^'), rest),
+        None => match spec.strip_prefix('~') {
+            Some(rest) => (Some('~'), rest),
+            None => (None, spec.as_str()),
+        },
+    };
+    let wanted: Option<Vec<u32>> = rest
+        .trim_start_matches('v')
+        .split('.')
+        .take_while(|part| *part != "x" && *part != "*")
+        .map(|part| part.parse().ok())
+        .collect();
+    let Some(wanted) = wanted.filter(|parts| !parts.is_empty() && parts.len() <= 3) else {
+        return false;
+    };
+
+    let actual = version_parts(version);
+    let mut minimum = [0; 3];
+    minimum[..wanted.len()].copy_from_slice(&wanted);
+    match operator {
+        Some('^') => actual[0] == wanted[0] && actual >= minimum,
+        Some('~') => {
+            let fixed = wanted.len().min(2);
+            actual[..fixed] == wanted[..fixed] && actual >= minimum
+        }
+        _ => actual[..wanted.len()] == wanted[..],
+    }
+}
+
+/// "v20.11.0" → [20, 11, 0]; las partes que faltan o no son números valen 0
+fn version_parts(version: &str) -> [u32; 3] {
+    let mut parts = [0; 3];
+    for (slot, part) in parts
+        .iter_mut()
+        .zip(version.strip_prefix('v').unwrap_or(version).split('.'))
+    {
+        *slot = part.parse().unwrap_or(0);
+    }
+    parts
+}
+
+/// Versión instalada a la que apunta el alias `name` (siguiendo la cadena de aliases); si el
+/// alias sigue un spec, la más nueva instalada que lo cumple. `None` si `name` no es un alias
+pub fn resolve_alias_installed(
+    name: &str,
+    aliases: &crate::core::aliases::Aliases,
+    installed: &[String],
+    available: &[NodeVersion],
+) -> Option<String> {
+    let chain = aliases.chain(name);
+    if chain.len() < 2 {
+        return None;
+    }
+    let target = chain.last()?;
+
+    find_installed_version(target, installed).or_else(|| find_installed_matching(target, installed, available))
+}
+
+/// La versión instalada más nueva que cumple `spec` (ver `matches_spec`); las LTS se reconocen
+/// con el índice `available`
+pub fn find_installed_matching(spec: &str, installed: &[String], available: &[NodeVersion]) -> Option<String> {
+    let lts_name = |version: &str| {
+        available
+            .iter()
+            .find(|v| v.version == version)
+            .and_then(|v| v.lts.name())
+    };
+    let mut matching: Vec<String> = installed
+        .iter()
+        .filter(|version| matches_spec(spec, version, lts_name(version)))
+        .cloned()
+        .collect();
+    sort_versions(&mut matching);
+    matching.into_iter().next()
+}
+
 /// Normaliza una versión asegurando que comience con 'v'
 #[allow(dead_code)] // Will be used in Phase 3 (install)
 pub fn normalize_version(version: &str) -> String {
@@ -140,6 +270,34 @@ pub fn version_number(version: &str) -> &str {
     version.strip_prefix('v').unwrap_or(version)
 }
 
+/// Obtiene la versión a partir del directorio raíz de un archivo de Node.js
+/// Ej: "node-v20.11.0-linux-x64" -> "v20.11.0"
+pub fn version_from_archive_root(name: &str) -> Option<String> {
+    let rest = name.strip_prefix("node-").unwrap_or(name);
+    let candidate = rest.split('-').next()?;
+    let number = candidate.strip_prefix('v')?;
+
+    let parts: Vec<&str> = number.split('.').collect();
+    if parts.len() != 3 || parts.iter().any(|p| p.is_empty() || !p.chars().all(|c| c.is_ascii_digit())) {
+        return None;
+    }
+
+    Some(candidate.to_string())
+}
+
+/// Ruta del ejecutable de Node.js dentro de una versión instalada (o extraída)
+pub fn node_binary_path(version_dir: &std::path::Path) -> std::path::PathBuf {
+    #[cfg(windows)]
+    {
+        version_dir.join("node.exe")
+    }
+
+    #[cfg(not(windows))]
+    {
+        version_dir.join("bin").join("node")
+    }
+}
+
 /// Filters versions based on criteria
 #[derive(Default)]
 pub struct VersionFilter {
@@ -220,39 +378,73 @@ pub fn format_version_display(version: &NodeVersion) -> String {
     }
 }
 
-/// Lee la versión actual desde el symlink "current" o desde archivo .nvm-version
-/// Intenta primero leer desde .nvm-version (más confiable en Windows), luego desde symlink
+/// Nombre que usa `nvm use system`: el Node.js del sistema, fuera de nvm
+pub const SYSTEM_VERSION: &str = "system";
+
+/// Versión que tenía la sesión antes del último `nvm use` (para `nvm use -` en la sesión)
+pub const SESSION_PREVIOUS_VAR: &str = "NVM_SESSION_PREVIOUS";
+
+/// Variable con la versión fijada en la sesión del shell (la escribe `nvm env` / `nvm use --shell`)
+pub const SESSION_VERSION_VAR: &str = "NVM_SESSION_VERSION";
+
+/// Versión fijada en la sesión del shell con `nvm env` / `nvm use` (función del shell)
+/// Puede ser `SYSTEM_VERSION`; se ignora si la versión ya no está instalada
+pub fn get_session_version(config: &crate::config::Config) -> Option<String> {
+    std::env::var(SESSION_VERSION_VAR).ok().filter(|version| {
+        version == SYSTEM_VERSION
+            || (!version.is_empty() && config.versions_dir().join(version).is_dir())
+    })
+}
+
+/// Versión por defecto (`nvm set-default`): el alias `default` si apunta a una versión instalada
+/// (si sigue un spec, la más nueva instalada que lo cumple)
+pub fn get_default_version(config: &crate::config::Config) -> Option<String> {
+    let aliases = crate::core::aliases::Aliases::load().ok()?;
+    let installed = crate::core::get_installed_versions(config).ok()?;
+    resolve_alias_installed(
+        crate::core::aliases::DEFAULT_ALIAS,
+        &aliases,
+        &installed,
+        &crate::core::read_cached_versions(config),
+    )
+}
+
+/// Versión activa para el usuario: la de la sesión si hay una, si no la global
+pub fn get_active_version(config: &crate::config::Config) -> Option<String> {
+    get_session_version(config).or_else(|| get_current_version(config))
+}
+
+/// Versión activa, o `SYSTEM_VERSION` si nvm no tiene ninguna activa y hay un Node.js del sistema en el PATH
+pub fn get_active_or_system(config: &crate::config::Config) -> Option<String> {
+    get_active_version(config)
+        .or_else(|| super::detection::find_system_node(config).map(|_| SYSTEM_VERSION.to_string()))
+}
+
+/// Versión global actual: la del link "current" (lo que realmente se ejecuta, aunque lo haya
+/// cambiado otro programa); sin link, o con el link roto, la registrada en state.json si sigue instalada
 pub fn get_current_version(config: &crate::config::Config) -> Option<String> {
     let current_link = config.current_dir();
 
+    // `exists` sigue el link: es falso si no hay link o si su destino ya no existe
     if !current_link.exists() {
-        return None;
+        let state = super::state::NvmState::load(config);
+        return state
+            .current_version()
+            .filter(|version| *version != SYSTEM_VERSION && config.versions_dir().join(version).is_dir())
+            .map(str::to_string);
     }
 
-    // Primero, intentar leer desde .nvm-version (persistencia)
-    let version_file = current_link.join(".nvm-version");
-    if version_file.exists() {
-        if let Ok(content) = std::fs::read_to_string(&version_file) {
-            let version = content.trim().to_string();
-            if !version.is_empty() {
-                return Some(version);
-            }
-        }
-    }
-
-    // Fallback: Leer el target del symlink
-    if let Ok(target) = std::fs::read_link(&current_link)
+    let target = std::fs::read_link(&current_link)
         .or_else(|_| current_link.canonicalize())
-    {
-        // Extraer el nombre de la versión del path
-        if let Some(version_name) = target.file_name() {
-            if let Some(version_str) = version_name.to_str() {
-                return Some(version_str.to_string());
-            }
-        }
-    }
+        .ok()?;
 
-    None
+    // En Unix el link apunta a versions/<versión>/bin
+    let version_dir = if target.file_name() == Some(std::ffi::OsStr::new("bin")) {
+        target.parent()?
+    } else {
+        target.as_path()
+    };
+    version_dir.file_name()?.to_str().map(str::to_string)
 }
 
 /// Formatea una versión instalada para mostrar en `nvm ls`
@@ -309,56 +501,37 @@ pub fn format_installed_version(
 /// Ordena versiones semánticamente (más reciente primero)
 pub fn sort_versions(versions: &mut [String]) {
     versions.sort_by(|a, b| {
-        let parse_version = |v: &str| -> (u32, u32, u32) {
-            let v_clean = v.strip_prefix('v').unwrap_or(v);
-            let parts: Vec<u32> = v_clean
-                .split('.')
-                .filter_map(|s| s.parse().ok())
-                .collect();
-
-            (
-                parts.first().copied().unwrap_or(0),
-                parts.get(1).copied().unwrap_or(0),
-                parts.get(2).copied().unwrap_or(0),
-            )
-        };
-
-        let a_ver = parse_version(a);
-        let b_ver = parse_version(b);
+        let a_ver = version_parts(a);
+        let b_ver = version_parts(b);
 
         // Orden descendente (más reciente primero)
         b_ver.cmp(&a_ver)
     });
 }
 
-/// Lee el archivo .nvmrc en el directorio especificado
-pub fn read_nvmrc(dir: &std::path::Path) -> Option<String> {
-    let nvmrc_path = dir.join(".nvmrc");
-
-    if !nvmrc_path.exists() {
-        return None;
-    }
+/// Archivos de versión por proyecto, en orden de prioridad dentro de un mismo directorio
+pub const VERSION_FILES: [&str; 2] = [".nvmrc", ".node-version"];
 
-    if let Ok(content) = std::fs::read_to_string(&nvmrc_path) {
+/// Lee el archivo de versión (.nvmrc o .node-version) en el directorio especificado
+pub fn read_version_file(dir: &std::path::Path) -> Option<(std::path::PathBuf, String)> {
+    VERSION_FILES.iter().find_map(|name| {
+        let path = dir.join(name);
+        let content = std::fs::read_to_string(&path).ok()?;
         // Tomar la primera línea y eliminar whitespace
         let version = content.lines().next()?.trim().to_string();
-        if !version.is_empty() {
-            return Some(version);
-        }
-    }
-
-    None
+        (!version.is_empty()).then_some((path, version))
+    })
 }
 
-/// Busca un archivo .nvmrc en el directorio actual y padres
+/// Busca un archivo de versión (.nvmrc o .node-version) en el directorio actual y padres
 pub fn find_nvmrc_in_tree(start_dir: Option<&std::path::Path>) -> Option<(std::path::PathBuf, String)> {
     let mut current_dir = start_dir
         .map(|p| p.to_path_buf())
         .or_else(|| std::env::current_dir().ok())?;
 
     loop {
-        if let Some(version) = read_nvmrc(&current_dir) {
-            return Some((current_dir.join(".nvmrc"), version));
+        if let Some(found) = read_version_file(&current_dir) {
+            return Some(found);
         }
 
         // Subir al directorio padre
@@ -432,6 +605,23 @@ fn test_resolve_lts() {
         assert_eq!(result, "v20.10.0");
     }
 
+    #[test]
+    fn test_resolve_version_follows_aliases() {
+        let versions = create_test_versions();
+        // Formato anterior de aliases.json: el alias es solo la versión
+        let aliases: crate::core::aliases::Aliases = serde_json::from_str(
+            r#"{"prod": "v18.19.0", "stable": "prod", "current": "lts/*", "a": "b", "b": "a"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(resolve_version_with("prod", &versions, &aliases).unwrap(), "v18.19.0");
+        assert_eq!(resolve_version_with("stable", &versions, &aliases).unwrap(), "v18.19.0");
+        assert_eq!(resolve_version_with("current", &versions, &aliases).unwrap(), "v20.10.0");
+
+        let err = resolve_version_with("a", &versions, &aliases).unwrap_err();
+        assert!(err.to_string().contains("Alias cycle: a -> b -> a"), "{}", err);
+    }
+
     #[test]
     fn test_resolve_lts_name() {
         let versions = create_test_versions();
@@ -439,9 +629,141 @@ fn test_resolve_lts_name() {
         assert_eq!(result, "v18.19.0");
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_current_version_follows_link_over_state() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let config = crate::config::Config::for_tests(temp.path());
+        for version in ["v18.19.0", "v20.11.0"] {
+            std::fs::create_dir_all(config.versions_dir().join(version).join("bin")).unwrap();
+        }
+
+        // state.json dice v18 pero el link lo cambió otro programa a v20
+        let mut state = crate::core::state::NvmState::default();
+        state.activate("v18.19.0");
+        state.save(&config).unwrap();
+        std::fs::create_dir_all(config.current_dir().parent().unwrap()).unwrap();
+        std::os::unix::fs::symlink(config.versions_dir().join("v20.11.0").join("bin"), config.current_dir()).unwrap();
+
+        assert_eq!(get_current_version(&config), Some("v20.11.0".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_current_version_falls_back_to_state() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let config = crate::config::Config::for_tests(temp.path());
+        std::fs::create_dir_all(config.versions_dir().join("v18.19.0").join("bin")).unwrap();
+
+        // Sin link: la versión de state.json, si sigue instalada
+        let mut state = crate::core::state::NvmState::default();
+        state.activate("v18.19.0");
+        state.save(&config).unwrap();
+        assert_eq!(get_current_version(&config), Some("v18.19.0".to_string()));
+
+        // Link roto hacia una versión desinstalada: state.json
+        std::fs::create_dir_all(config.current_dir().parent().unwrap()).unwrap();
+        std::os::unix::fs::symlink(config.versions_dir().join("v16.20.0").join("bin"), config.current_dir()).unwrap();
+        assert_eq!(get_current_version(&config), Some("v18.19.0".to_string()));
+
+        // Una versión de state.json que ya no está instalada no cuenta
+        std::fs::remove_dir_all(config.versions_dir().join("v18.19.0")).unwrap();
+        assert_eq!(get_current_version(&config), None);
+
+        // `nvm use system` registra el sistema: no hay versión de nvm activa
+        state.activate(SYSTEM_VERSION);
+        state.save(&config).unwrap();
+        assert_eq!(get_current_version(&config), None);
+    }
+
+    #[test]
+    fn test_matches_spec() {
+        assert!(matches_spec("lts/*", "v20.10.0", Some("Iron")));
+        assert!(!matches_spec("lts/*", "v19.0.0", None));
+        assert!(matches_spec("lts/iron", "v20.10.0", Some("Iron")));
+        assert!(matches_spec("20", "v20.10.0", None));
+        assert!(matches_spec("20.x", "v20.10.0", None));
+        assert!(!matches_spec("2", "v20.10.0", None));
+        assert!(matches_spec("^20.5", "v20.10.0", None));
+        assert!(!matches_spec("^20.11", "v20.10.0", None));
+        assert!(matches_spec("~20.10", "v20.10.3", None));
+        assert!(!matches_spec("~20.10", "v20.11.0", None));
+        assert!(!matches_spec("stable", "v20.10.0", None));
+    }
+
+    #[test]
+    fn test_resolve_alias_installed_tracks_spec() {
+        let available = create_test_versions();
+        let installed = vec!["v18.19.0".to_string(), "v19.0.0".to_string()];
+        let mut aliases = crate::core::aliases::Aliases::new();
+        let mut stable = crate::core::aliases::AliasEntry::new("lts/*".to_string());
+        stable.tracking = true;
+        aliases.insert("stable".to_string(), stable);
+        aliases.set("pinned".to_string(), "v20.10.0".to_string());
+
+        // La LTS más nueva instalada, no la última publicada (v20.10.0)
+        assert_eq!(
+            resolve_alias_installed("stable", &aliases, &installed, &available),
+            Some("v18.19.0".to_string())
+        );
+        assert_eq!(resolve_alias_installed("pinned", &aliases, &installed, &available), None);
+        assert_eq!(resolve_alias_installed("18", &aliases, &installed, &available), None);
+        assert_eq!(resolve_version("^18", &available).unwrap(), "v18.19.0");
+    }
+
+    #[test]
+    fn test_version_from_archive_root() {
+        assert_eq!(
+            version_from_archive_root("node-v20.11.0-linux-x64"),
+            Some("v20.11.0".to_string())
+        );
+        assert_eq!(
+            version_from_archive_root("node-v18.19.0-darwin-arm64"),
+            Some("v18.19.0".to_string())
+        );
+        assert_eq!(version_from_archive_root("v16.20.2"), Some("v16.20.2".to_string()));
+        assert_eq!(version_from_archive_root("node-latest-linux-x64"), None);
+        assert_eq!(version_from_archive_root("package"), None);
+    }
+
+    #[test]
+    fn test_find_installed_version() {
+        let installed = vec![
+            "v18.19.0".to_string(),
+            "v20.9.0".to_string(),
+            "v20.11.1".to_string(),
+        ];
+        assert_eq!(find_installed_version("20", &installed), Some("v20.11.1".to_string()));
+        assert_eq!(find_installed_version("v20.9", &installed), Some("v20.9.0".to_string()));
+        assert_eq!(find_installed_version("18.19.0", &installed), Some("v18.19.0".to_string()));
+        assert_eq!(find_installed_version("2", &installed), None);
+        assert_eq!(find_installed_version("22", &installed), None);
+    }
+
     #[test]
     fn test_normalize_version() {
         assert_eq!(normalize_version("18.0.0"), "v18.0.0");
         assert_eq!(normalize_version("v18.0.0"), "v18.0.0");
     }
+
+    #[test]
+    fn test_find_version_file_in_tree() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let project = temp.path().join("project");
+        let nested = project.join("src").join("lib");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(project.join(".node-version"), "20.11.0\n").unwrap();
+
+        let (path, version) = find_nvmrc_in_tree(Some(&nested)).unwrap();
+        assert_eq!(path, project.join(".node-version"));
+        assert_eq!(version, "20.11.0");
+
+        // .nvmrc tiene prioridad en el mismo directorio
+        std::fs::write(project.join(".nvmrc"), "lts/iron\n").unwrap();
+        assert_eq!(find_nvmrc_in_tree(Some(&nested)).unwrap().1, "lts/iron");
+
+        // Un archivo vacío se ignora
+        std::fs::write(project.join(".nvmrc"), "\n").unwrap();
+        assert_eq!(find_nvmrc_in_tree(Some(&nested)).unwrap().1, "20.11.0");
+    }
 }
@@ -33,9 +33,50 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Install a Node.js version
+    #[command(group(clap::ArgGroup::new("archive_origin").args(["from_archive", "from_url", "source"]).multiple(true)))]
     Install {
         /// Version to install (e.g., 18.19.0, lts, latest)
-        version: String,
+        #[arg(required_unless_present_any = ["from_archive", "from_url"])]
+        version: Option<String>,
+        /// Install from a local Node.js archive (version taken from its root directory)
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["version", "from_url"])]
+        from_archive: Option<PathBuf>,
+        /// Download and install a Node.js archive from an arbitrary URL
+        #[arg(long, value_name = "URL", conflicts_with = "version")]
+        from_url: Option<String>,
+        /// Expected SHA256 of the archive given with --from-archive, --from-url or --source
+        #[arg(long, value_name = "HASH", requires = "archive_origin")]
+        sha256: Option<String>,
+        /// Build from the source tarball (node-<version>.tar.gz, or --from-archive)
+        #[arg(long, conflicts_with = "from_url")]
+        source: bool,
+        /// Extra flags passed to ./configure when building from source
+        #[arg(long, value_name = "FLAGS", allow_hyphen_values = true, requires = "source")]
+        configure_flags: Option<String>,
+        /// Parallel make jobs when building from source (default: CPU count)
+        #[arg(short = 'j', long, value_name = "N", requires = "source")]
+        jobs: Option<usize>,
+        /// Don't install the packages listed in $NVM_HOME/default-packages
+        #[arg(long)]
+        skip_default_packages: bool,
+        /// Reinstall the global npm packages of an installed version (e.g., 20, v20.11.0)
+        #[arg(long, value_name = "VERSION")]
+        reinstall_packages_from: Option<String>,
+        /// Keep the exact versions of reinstalled packages instead of the latest
+        #[arg(long, requires = "reinstall_packages_from")]
+        keep_package_versions: bool,
+        /// Run `corepack enable` for the new version (default from config.json)
+        #[arg(long, conflicts_with = "no_corepack")]
+        corepack: bool,
+        /// Don't enable corepack even if config.json enables it
+        #[arg(long)]
+        no_corepack: bool,
+        /// Also prepare the packageManager from the nearest package.json (implies --corepack)
+        #[arg(long, conflicts_with = "no_corepack")]
+        corepack_prepare: bool,
+        /// Hardlink files identical to other installed versions (default from config.json)
+        #[arg(long)]
+        dedupe: bool,
     },
 
     /// Uninstall a Node.js version
@@ -49,8 +90,11 @@ enum Commands {
 
     /// Switch to a Node.js version
     Use {
-        /// Version to use (optional, will check .nvmrc or default)
+        /// Version to use, or "system" for the OS-provided Node.js (optional, will check .nvmrc)
         version: Option<String>,
+        /// Print shell code that switches only the current session (used by the `nvm env` function)
+        #[arg(long, value_parser = ["bash", "zsh", "fish", "nu", "xonsh", "elvish"])]
+        shell: Option<String>,
     },
 
     /// List installed versions
@@ -65,7 +109,42 @@ enum Commands {
     },
 
     /// Show current active version
-    Current,
+    Current {
+        /// Also show where the version comes from, its binary and whether the node on PATH matches
+        #[arg(short, long)]
+        verbose: bool,
+        /// Print the verbose report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print the installed version requested by the nearest .nvmrc/.node-version (no network; used by the cd hook)
+    Resolve,
+
+    /// Print shell code that pins Node.js to this session: eval "$(nvm env)"
+    #[cfg(not(windows))]
+    Env {
+        /// Shell to generate code for (default: detected from $SHELL)
+        #[arg(long, value_parser = ["bash", "zsh", "fish", "nu", "xonsh", "elvish"])]
+        shell: Option<String>,
+    },
+
+    /// Print shell code that removes nvm's Node.js from this session: eval "$(nvm deactivate)"
+    #[cfg(not(windows))]
+    Deactivate {
+        /// Shell to generate code for (default: detected from $SHELL)
+        #[arg(long, value_parser = ["bash", "zsh", "fish", "nu", "xonsh", "elvish"])]
+        shell: Option<String>,
+    },
+
+    /// Show where an installed version came from (source, checksum, install date)
+    Info {
+        /// Installed version (exact, partial or alias)
+        version: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
 
     /// Create an alias for a version
     Alias {
@@ -73,6 +152,12 @@ enum Commands {
         name: String,
         /// Version to alias
         version: String,
+        /// Store the spec (lts/*, ^20, another alias) and re-resolve it on each use instead of freezing it
+        #[arg(long)]
+        track: bool,
+        /// Description shown by `nvm aliases`
+        #[arg(short, long)]
+        description: Option<String>,
     },
 
     /// Remove an alias
@@ -101,20 +186,59 @@ enum Commands {
         fix: bool,
     },
 
-    /// Remove unused versions (keep current and LTS)
+    /// Remove unused versions (keep current, default and LTS)
     Cleanup {
         /// Skip confirmation prompt
         #[arg(short, long)]
         yes: bool,
     },
 
+    /// Replace identical files across installed versions with hardlinks
+    Dedupe,
+
+    /// Regenerate the shims in $NVM_HOME/shims (e.g., after `npm install -g`)
+    Reshim,
+
+    /// Run a tool with the version resolved for the current directory (used by the shims)
+    #[command(hide = true, disable_help_flag = true)]
+    Shim {
+        /// Tool to run (node, npm, npx, corepack or a global package bin)
+        name: String,
+        /// Arguments passed to the tool
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<std::ffi::OsString>,
+    },
+
+    /// Run a command with an installed Node.js version: nvm exec 20 node app.js
+    Exec {
+        /// Installed version (exact, partial or alias)
+        version: String,
+        /// Command and its arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true)]
+        command: Vec<std::ffi::OsString>,
+    },
+
+    /// Print a shell completion script: source <(nvm completions bash)
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_parser = ["bash", "zsh", "fish", "nu"])]
+        shell: String,
+    },
+
+    /// Print completion candidates, one per line (used by the completion scripts)
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        #[arg(value_parser = ["versions", "aliases"])]
+        kind: String,
+    },
+
     /// Update nvm itself
     #[cfg(feature = "self-update")]
     SelfUpdate,
 
-    /// Set default version for new shells
+    /// Set the default version (new shells, shims, uninstall fallback)
     SetDefault {
-        /// Version to set as default
+        /// Installed version to set as default (exact, partial or alias)
         version: String,
     },
 
@@ -217,16 +341,76 @@ async fn main() -> Result<()> {
             Cli::command().print_help()?;
             println!();
         }
-        Some(Commands::Install { version }) => {
-            commands::install::install(&version, &config).await?;
+        Some(Commands::Install {
+            version,
+            from_archive,
+            from_url,
+            sha256,
+            source,
+            configure_flags,
+            jobs,
+            skip_default_packages,
+            reinstall_packages_from,
+            keep_package_versions,
+            corepack,
+            no_corepack,
+            corepack_prepare,
+            dedupe,
+        }) => {
+            let corepack = match (corepack, no_corepack) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            };
+            let post = commands::install::PostInstall::new(
+                skip_default_packages,
+                reinstall_packages_from.as_deref(),
+                keep_package_versions,
+                corepack,
+                corepack_prepare,
+                dedupe,
+                &config,
+            )?;
+            if source {
+                let options = core::build::BuildOptions::new(configure_flags.as_deref(), jobs);
+                commands::install::install_from_source(
+                    version.as_deref(),
+                    from_archive.as_deref(),
+                    sha256.as_deref(),
+                    &options,
+                    &post,
+                    &config,
+                )
+                .await?;
+            } else if let Some(path) = from_archive {
+                commands::install::install_from_archive(&path, sha256.as_deref(), &post, &config).await?;
+            } else if let Some(url) = from_url {
+                commands::install::install_from_url(&url, sha256.as_deref(), &post, &config).await?;
+            } else if let Some(version) = version {
+                commands::install::install(&version, &post, &config).await?;
+            }
         }
 
         Some(Commands::Uninstall { version, force }) => {
             commands::uninstall::uninstall(&version, force, &config).await?;
         }
 
-        Some(Commands::Use { version }) => {
-            commands::use_version::use_version(version, &config).await?;
+        Some(Commands::Use { version, shell }) => {
+            commands::use_version::use_version(version, shell, &config).await?;
+        }
+
+        Some(Commands::Resolve) => {
+            commands::use_version::resolve(&config)?;
+        }
+
+        #[cfg(not(windows))]
+        Some(Commands::Env { shell }) => {
+            commands::env::print_env(shell.as_deref(), &config)?;
+        }
+
+        #[cfg(not(windows))]
+        Some(Commands::Deactivate { shell }) => {
+            commands::env::print_deactivate(shell.as_deref(), &config)?;
         }
 
         Some(Commands::Ls) => {
@@ -237,20 +421,24 @@ async fn main() -> Result<()> {
             commands::list::list_remote(lts, &config).await?;
         }
 
-        Some(Commands::Current) => {
-            commands::list::show_current(&config)?;
+        Some(Commands::Current { verbose, json }) => {
+            commands::list::show_current(verbose, json, &config)?;
         }
 
-        Some(Commands::Alias { name, version }) => {
-            commands::alias::create_alias(name, version, &config).await?;
+        Some(Commands::Info { version, json }) => {
+            commands::info::show_info(&version, json, &config)?;
+        }
+
+        Some(Commands::Alias { name, version, track, description }) => {
+            commands::alias::create_alias(name, version, track, description, &config).await?;
         }
 
         Some(Commands::Unalias { name }) => {
-            commands::alias::remove_alias(name)?;
+            commands::alias::remove_alias(name, &config)?;
         }
 
         Some(Commands::Aliases) => {
-            commands::alias::list_aliases()?;
+            commands::alias::list_aliases(&config)?;
         }
 
         #[cfg(windows)]
@@ -274,7 +462,7 @@ async fn main() -> Result<()> {
             }
 
             if !all && !system {
-                commands::doctor::run_diagnostics(&config, fix)?;
+                commands::doctor::run_diagnostics(&config, fix).await?;
             }
         }
 
@@ -282,8 +470,32 @@ async fn main() -> Result<()> {
             commands::misc::cleanup(yes, &config).await?;
         }
 
+        Some(Commands::Dedupe) => {
+            commands::misc::dedupe(&config)?;
+        }
+
+        Some(Commands::Reshim) => {
+            commands::shims::reshim(&config)?;
+        }
+
+        Some(Commands::Shim { name, args }) => {
+            commands::shims::exec_shim(&name, args, &config)?;
+        }
+
+        Some(Commands::Exec { version, command }) => {
+            commands::exec::exec(&version, command, &config)?;
+        }
+
+        Some(Commands::Completions { shell }) => {
+            commands::completions::print_completions(&shell, Cli::command())?;
+        }
+
+        Some(Commands::Complete { kind }) => {
+            commands::completions::print_candidates(&kind, &config);
+        }
+
         Some(Commands::SetDefault { version }) => {
-            commands::misc::set_default(version)?;
+            commands::misc::set_default(&version, &config)?;
         }
 
         Some(Commands::Lang { locale }) => {
@@ -291,7 +503,7 @@ async fn main() -> Result<()> {
         }
 
         Some(Commands::InstallSelf { version, dir, with_self_update }) => {
-            commands::self_management::install_self(version, dir, with_self_update).await?;
+            commands::self_management::install_self(version, dir, with_self_update, &config).await?;
         }
 
         Some(Commands::UninstallSelf { dir, yes, purge, remove_config }) => {
@@ -299,7 +511,7 @@ async fn main() -> Result<()> {
         }
 
         Some(Commands::UpdateSelf { version, with_self_update }) => {
-            commands::self_management::update_self(version, with_self_update).await?;
+            commands::self_management::update_self(version, with_self_update, &config).await?;
         }
 
         Some(Commands::Stats { json }) => {
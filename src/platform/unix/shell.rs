@@ -3,38 +3,157 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub(crate) enum ShellType {
     Bash,
     Zsh,
     Fish,
+    Nushell,
+    Xonsh,
+    Elvish,
 }
 
-const NVM_BLOCK_START: &str = "# >>> nvm-rs >>>";
-const NVM_BLOCK_END: &str = "# <<< nvm-rs <<<";
+impl ShellType {
+    pub(crate) const ALL: [ShellType; 6] = [
+        ShellType::Bash,
+        ShellType::Zsh,
+        ShellType::Fish,
+        ShellType::Nushell,
+        ShellType::Xonsh,
+        ShellType::Elvish,
+    ];
 
-pub(crate) fn detect_shell_config() -> Result<(PathBuf, ShellType)> {
-    let home = dirs::home_dir().ok_or_else(|| message("Home directory not found"))?;
-    let shell = env::var("SHELL").unwrap_or_default();
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "bash" => Some(ShellType::Bash),
+            "zsh" => Some(ShellType::Zsh),
+            "fish" => Some(ShellType::Fish),
+            "nu" | "nushell" => Some(ShellType::Nushell),
+            "xonsh" => Some(ShellType::Xonsh),
+            "elvish" => Some(ShellType::Elvish),
+            _ => None,
+        }
+    }
 
-    if shell.contains("fish") {
-        return Ok((home.join(".config").join("fish").join("config.fish"), ShellType::Fish));
+    /// Shell a partir de la ruta del ejecutable (ej: $SHELL = /usr/bin/nu)
+    /// Los nombres con versión o sufijo (zsh5, zsh-static, fish3) se reconocen como antes por subcadena
+    fn from_path(shell: &str) -> Option<Self> {
+        let name = Path::new(shell).file_name()?.to_str()?;
+        Self::from_name(name).or_else(|| {
+            if name.contains("zsh") {
+                Some(ShellType::Zsh)
+            } else if name.contains("fish") {
+                Some(ShellType::Fish)
+            } else {
+                None
+            }
+        })
     }
 
-    if shell.contains("zsh") {
-        return Ok((home.join(".zshrc"), ShellType::Zsh));
+    /// Shell del usuario según $SHELL (bash si no se reconoce)
+    pub(crate) fn detect() -> Self {
+        let shell = env::var("SHELL").unwrap_or_default();
+        Self::from_path(&shell).unwrap_or(ShellType::Bash)
     }
 
-    if shell.contains("bash") {
-        let bashrc = home.join(".bashrc");
-        let bash_profile = home.join(".bash_profile");
-        if bashrc.exists() {
-            return Ok((bashrc, ShellType::Bash));
-        }
-        if bash_profile.exists() {
-            return Ok((bash_profile, ShellType::Bash));
+    /// La función `nvm` y el hook de cambio de directorio solo existen para estos shells
+    pub(crate) fn supports_wrapper(self) -> bool {
+        matches!(self, ShellType::Bash | ShellType::Zsh | ShellType::Fish)
+    }
+
+    /// Archivo de configuración del shell
+    fn config_path(self, home: &Path) -> PathBuf {
+        match self {
+            ShellType::Bash => {
+                let bashrc = home.join(".bashrc");
+                let bash_profile = home.join(".bash_profile");
+                if !bashrc.exists() && bash_profile.exists() {
+                    bash_profile
+                } else {
+                    bashrc
+                }
+            }
+            ShellType::Zsh => home.join(".zshrc"),
+            ShellType::Fish => home.join(".config").join("fish").join("config.fish"),
+            // nushell usa el directorio de configuración de la plataforma salvo con XDG_CONFIG_HOME
+            ShellType::Nushell => env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(dirs::config_dir)
+                .unwrap_or_else(|| home.join(".config"))
+                .join("nushell")
+                .join("config.nu"),
+            ShellType::Xonsh => home.join(".xonshrc"),
+            ShellType::Elvish => {
+                let rc = env::var_os("XDG_CONFIG_HOME")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| home.join(".config"))
+                    .join("elvish")
+                    .join("rc.elv");
+                // Ubicación anterior a elvish 0.17
+                let legacy = home.join(".elvish").join("rc.elv");
+                if !rc.exists() && legacy.exists() {
+                    legacy
+                } else {
+                    rc
+                }
+            }
         }
-        return Ok((bashrc, ShellType::Bash));
+    }
+}
+
+/// Hook de cambio de directorio incluido en el bloque del shell
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum CdHook {
+    Off,
+    /// Cambiar a la versión del .nvmrc si está instalada
+    Use,
+    /// Además, instalarla si falta
+    UseAndInstall,
+}
+
+/// Opciones del bloque de nvm-rs que dependen de la configuración
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct ShellBlockOptions {
+    pub cd_hook: CdHook,
+    /// Poner $NVM_HOME/shims en el PATH antes del link global
+    pub shims: bool,
+    /// Cargar `nvm completions` al iniciar el shell
+    pub completions: bool,
+}
+
+impl ShellBlockOptions {
+    pub(crate) fn from_config(config: &crate::config::Config) -> Self {
+        let cd_hook = match (config.use_on_cd, config.install_on_cd) {
+            (false, _) => CdHook::Off,
+            (true, false) => CdHook::Use,
+            (true, true) => CdHook::UseAndInstall,
+        };
+        ShellBlockOptions { cd_hook, shims: config.shims, completions: config.completions }
+    }
+
+    /// Opciones de config.json / variables de entorno; todo desactivado si no se puede leer
+    pub(crate) fn configured() -> Self {
+        crate::config::Config::new()
+            .map(|config| Self::from_config(&config))
+            .unwrap_or_default()
+    }
+}
+
+impl Default for ShellBlockOptions {
+    fn default() -> Self {
+        ShellBlockOptions { cd_hook: CdHook::Off, shims: false, completions: false }
+    }
+}
+
+const NVM_BLOCK_START: &str = "# >>> nvm-rs >>>";
+const NVM_BLOCK_END: &str = "# <<< nvm-rs <<<";
+
+pub(crate) fn detect_shell_config() -> Result<(PathBuf, ShellType)> {
+    let home = dirs::home_dir().ok_or_else(|| message("Home directory not found"))?;
+    let shell = env::var("SHELL").unwrap_or_default();
+
+    if let Some(shell) = ShellType::from_path(&shell) {
+        return Ok((shell.config_path(&home), shell));
     }
 
     let zshrc = home.join(".zshrc");
@@ -45,25 +164,250 @@ pub(crate) fn detect_shell_config() -> Result<(PathBuf, ShellType)> {
     Ok((home.join(".bashrc"), ShellType::Bash))
 }
 
-pub(crate) fn build_shell_block(nvm_dir: &Path, shell: ShellType) -> String {
+/// Archivos de configuración existentes de todos los shells soportados
+/// (el bloque puede estar en varios si se cambió de shell)
+pub(crate) fn existing_shell_configs() -> Vec<(PathBuf, ShellType)> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+
+    let mut configs: Vec<(PathBuf, ShellType)> = Vec::new();
+    for shell in ShellType::ALL {
+        let path = shell.config_path(&home);
+        if path.is_file() && !configs.iter().any(|(existing, _)| *existing == path) {
+            configs.push((path, shell));
+        }
+    }
+    configs
+}
+
+pub(crate) fn build_shell_block(nvm_dir: &Path, shell: ShellType, options: ShellBlockOptions) -> String {
     let nvm_dir_str = nvm_dir.to_string_lossy();
 
-    match shell {
-        ShellType::Fish => format!(
-            "{start}\nset -gx NVM_HOME \"{nvm_dir}\"\nset -gx NVM_BIN \"$NVM_HOME/bin\"\nset -gx NVM_NODE \"$NVM_HOME/current/bin\"\nfish_add_path $NVM_BIN $NVM_NODE\n{end}\n",
-            start = NVM_BLOCK_START,
-            end = NVM_BLOCK_END,
+    let env = match (shell, options.shims) {
+        (ShellType::Fish, false) => format!(
+            "set -gx NVM_HOME \"{nvm_dir}\"\nset -gx NVM_BIN \"$NVM_HOME/bin\"\nset -gx NVM_NODE \"$NVM_HOME/current/bin\"\nfish_add_path $NVM_BIN $NVM_NODE\n",
+            nvm_dir = nvm_dir_str
+        ),
+        (ShellType::Fish, true) => format!(
+            "set -gx NVM_HOME \"{nvm_dir}\"\nset -gx NVM_BIN \"$NVM_HOME/bin\"\nset -gx NVM_NODE \"$NVM_HOME/current/bin\"\nfish_add_path $NVM_BIN $NVM_HOME/shims $NVM_NODE\n",
             nvm_dir = nvm_dir_str
         ),
-        ShellType::Bash | ShellType::Zsh => format!(
-            "{start}\nexport NVM_HOME=\"{nvm_dir}\"\nexport NVM_BIN=\"$NVM_HOME/bin\"\nexport NVM_NODE=\"$NVM_HOME/current/bin\"\nexport PATH=\"$NVM_BIN:$NVM_NODE:$PATH\"\n{end}\n",
-            start = NVM_BLOCK_START,
-            end = NVM_BLOCK_END,
+        (ShellType::Bash | ShellType::Zsh, false) => format!(
+            "export NVM_HOME=\"{nvm_dir}\"\nexport NVM_BIN=\"$NVM_HOME/bin\"\nexport NVM_NODE=\"$NVM_HOME/current/bin\"\nexport PATH=\"$NVM_BIN:$NVM_NODE:$PATH\"\n",
             nvm_dir = nvm_dir_str
         ),
+        (ShellType::Bash | ShellType::Zsh, true) => format!(
+            "export NVM_HOME=\"{nvm_dir}\"\nexport NVM_BIN=\"$NVM_HOME/bin\"\nexport NVM_NODE=\"$NVM_HOME/current/bin\"\nexport PATH=\"$NVM_BIN:$NVM_HOME/shims:$NVM_NODE:$PATH\"\n",
+            nvm_dir = nvm_dir_str
+        ),
+        (ShellType::Nushell, shims) => format!(
+            "$env.NVM_HOME = \"{nvm_dir}\"\n$env.NVM_BIN = ($env.NVM_HOME | path join \"bin\")\n$env.NVM_NODE = ($env.NVM_HOME | path join \"current\" \"bin\")\n$env.PATH = ($env.PATH | split row (char esep) | prepend [$env.NVM_BIN {shims}$env.NVM_NODE] | uniq)\n",
+            nvm_dir = nvm_dir_str,
+            shims = if shims { "($env.NVM_HOME | path join \"shims\") " } else { "" }
+        ),
+        // $PATH.insert(0, ...) en orden inverso para que NVM_BIN quede primero
+        (ShellType::Xonsh, shims) => format!(
+            "$NVM_HOME = \"{nvm_dir}\"\n$NVM_BIN = $NVM_HOME + \"/bin\"\n$NVM_NODE = $NVM_HOME + \"/current/bin\"\n$PATH.insert(0, $NVM_NODE)\n{shims}$PATH.insert(0, $NVM_BIN)\n",
+            nvm_dir = nvm_dir_str,
+            shims = if shims { "$PATH.insert(0, $NVM_HOME + \"/shims\")\n" } else { "" }
+        ),
+        (ShellType::Elvish, shims) => format!(
+            "set-env NVM_HOME \"{nvm_dir}\"\nset-env NVM_BIN $E:NVM_HOME/bin\nset-env NVM_NODE $E:NVM_HOME/current/bin\nset paths = [$E:NVM_BIN {shims}$E:NVM_NODE $@paths]\n",
+            nvm_dir = nvm_dir_str,
+            shims = if shims { "$E:NVM_HOME/shims " } else { "" }
+        ),
+    };
+
+    // El hook cambia la versión por sesión, así que `nvm use` también debe hacerlo
+    // En nushell, xonsh y elvish el cambio por sesión se aplica a mano con `nvm env`
+    let hook = match options.cd_hook {
+        CdHook::Off => String::new(),
+        _ if !shell.supports_wrapper() => String::new(),
+        cd_hook => {
+            format!("{}{}", build_session_wrapper(shell), build_cd_hook(shell, cd_hook))
+        }
+    };
+
+    let completions = if options.completions { build_completions(shell) } else { "" };
+
+    format!(
+        "{start}\n{env}{hook}{completions}{end}\n",
+        start = NVM_BLOCK_START,
+        end = NVM_BLOCK_END,
+        env = env,
+        hook = hook,
+        completions = completions
+    )
+}
+
+/// Carga de `nvm completions` al iniciar el shell; zsh necesita `compinit` antes del bloque
+/// nushell solo carga scripts al parsear la configuración: se instalan a mano (ver README)
+fn build_completions(shell: ShellType) -> &'static str {
+    match shell {
+        ShellType::Bash => "eval \"$(command nvm completions bash 2>/dev/null)\"\n",
+        ShellType::Zsh => "(( $+functions[compdef] )) && eval \"$(command nvm completions zsh 2>/dev/null)\"\n",
+        ShellType::Fish => "command nvm completions fish 2>/dev/null | source\n",
+        ShellType::Nushell | ShellType::Xonsh | ShellType::Elvish => "",
     }
 }
 
+/// Hook que, al cambiar de directorio, cambia la sesión a la versión del .nvmrc más cercano
+/// `nvm resolve` no usa la red; solo se cambia si la versión es distinta a la de la sesión
+fn build_cd_hook(shell: ShellType, cd_hook: CdHook) -> String {
+    let not_installed = crate::commands::use_version::RESOLVE_NOT_INSTALLED;
+
+    match shell {
+        ShellType::Fish => {
+            let missing = if cd_hook == CdHook::UseAndInstall {
+                "        command nvm install $version >&2; or return\n        set version (command nvm resolve 2>/dev/null); or return\n"
+            } else {
+                "        echo \"nvm: Node.js $version is not installed (run: nvm install $version)\" >&2\n        return\n"
+            };
+            format!(
+                "function _nvm_rs_cd_hook --on-variable PWD\n    set -l version (command nvm resolve 2>/dev/null)\n    set -l resolve_status $status\n    if test $resolve_status -eq {not_installed}\n{missing}    else if test $resolve_status -ne 0\n        return\n    end\n    if test -n \"$version\"; and test \"$version\" != \"$NVM_SESSION_VERSION\"\n        command nvm use --shell fish $version | source\n    end\nend\n_nvm_rs_cd_hook\n",
+                not_installed = not_installed,
+                missing = missing
+            )
+        }
+        ShellType::Nushell | ShellType::Xonsh | ShellType::Elvish => String::new(),
+        ShellType::Bash | ShellType::Zsh => {
+            let name = if shell == ShellType::Zsh { "zsh" } else { "bash" };
+            let missing = if cd_hook == CdHook::UseAndInstall {
+                "command nvm install \"$version\" >&2 && version=\"$(command nvm resolve 2>/dev/null)\" || return"
+            } else {
+                "echo \"nvm: Node.js $version is not installed (run: nvm install $version)\" >&2; return"
+            };
+            // bash no tiene evento de cambio de directorio: PROMPT_COMMAND + último $PWD
+            let register = if shell == ShellType::Zsh {
+                "autoload -U add-zsh-hook\nadd-zsh-hook chpwd _nvm_rs_cd_hook\n_nvm_rs_cd_hook\n"
+            } else {
+                "case \";${PROMPT_COMMAND:-};\" in\n    *\";_nvm_rs_cd_hook;\"*) ;;\n    *) PROMPT_COMMAND=\"_nvm_rs_cd_hook${PROMPT_COMMAND:+;$PROMPT_COMMAND}\" ;;\nesac\n"
+            };
+            format!(
+                "_nvm_rs_cd_hook() {{\n    [ \"$PWD\" = \"${{_NVM_RS_LAST_PWD:-}}\" ] && return\n    _NVM_RS_LAST_PWD=\"$PWD\"\n    local version\n    version=\"$(command nvm resolve 2>/dev/null)\"\n    case $? in\n        0) ;;\n        {not_installed}) {missing} ;;\n        *) return ;;\n    esac\n    [ -n \"$version\" ] && [ \"$version\" != \"${{NVM_SESSION_VERSION:-}}\" ] || return\n    local nvm_env\n    nvm_env=\"$(command nvm use --shell {name} \"$version\")\" && eval \"$nvm_env\"\n}}\n{register}",
+                not_installed = not_installed,
+                missing = missing,
+                name = name,
+                register = register
+            )
+        }
+    }
+}
+
+/// Código que exporta variables y reemplaza PATH solo en la sesión actual (`nvm env`)
+pub(crate) fn build_session_env(
+    shell: ShellType,
+    vars: &[(&str, String)],
+    unset: &[&str],
+    path: &[PathBuf],
+) -> String {
+    let mut output = String::new();
+
+    match shell {
+        ShellType::Fish => {
+            for (name, value) in vars {
+                output.push_str(&format!("set -gx {} {}\n", name, quote(shell, value)));
+            }
+            for name in unset {
+                output.push_str(&format!("set -e {}\n", name));
+            }
+            let entries: Vec<String> = path
+                .iter()
+                .map(|entry| quote(shell, &entry.to_string_lossy()))
+                .collect();
+            output.push_str(&format!("set -gx PATH {}\n", entries.join(" ")));
+        }
+        ShellType::Bash | ShellType::Zsh => {
+            for (name, value) in vars {
+                output.push_str(&format!("export {}={}\n", name, quote(shell, value)));
+            }
+            for name in unset {
+                output.push_str(&format!("unset {}\n", name));
+            }
+            let joined = env::join_paths(path)
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default();
+            output.push_str(&format!("export PATH={}\n", quote(shell, &joined)));
+            // bash recuerda la ruta de cada comando; olvidar las de la versión anterior
+            output.push_str("hash -r 2>/dev/null\n");
+        }
+        // nushell no evalúa texto: un registro JSON para `from json | load-env`
+        // load-env no puede eliminar variables, las eliminadas quedan vacías
+        ShellType::Nushell => {
+            let mut record = serde_json::Map::new();
+            for (name, value) in vars {
+                record.insert(name.to_string(), serde_json::Value::from(value.as_str()));
+            }
+            for name in unset {
+                record.insert(name.to_string(), serde_json::Value::from(""));
+            }
+            let entries: Vec<String> = path.iter().map(|entry| entry.to_string_lossy().to_string()).collect();
+            record.insert("PATH".to_string(), serde_json::Value::from(entries));
+            output.push_str(&serde_json::Value::Object(record).to_string());
+            output.push('\n');
+        }
+        ShellType::Xonsh => {
+            for (name, value) in vars {
+                output.push_str(&format!("${} = {}\n", name, quote(shell, value)));
+            }
+            for name in unset {
+                output.push_str(&format!("${{...}}.pop({}, None)\n", quote(shell, name)));
+            }
+            let entries: Vec<String> = path
+                .iter()
+                .map(|entry| quote(shell, &entry.to_string_lossy()))
+                .collect();
+            output.push_str(&format!("$PATH = [{}]\n", entries.join(", ")));
+        }
+        ShellType::Elvish => {
+            for (name, value) in vars {
+                output.push_str(&format!("set-env {} {}\n", name, quote(shell, value)));
+            }
+            for name in unset {
+                output.push_str(&format!("unset-env {}\n", name));
+            }
+            let entries: Vec<String> = path
+                .iter()
+                .map(|entry| quote(shell, &entry.to_string_lossy()))
+                .collect();
+            output.push_str(&format!("set paths = [{}]\n", entries.join(" ")));
+        }
+    }
+
+    output
+}
+
+/// Función `nvm` que aplica `nvm use` y `nvm deactivate` en la sesión actual en lugar de cambiar
+/// $NVM_HOME/current; `command nvm use ...` sigue cambiando la versión global
+pub(crate) fn build_session_wrapper(shell: ShellType) -> String {
+    match shell {
+        ShellType::Fish => "function nvm\n    if test (count $argv) -gt 0; and test \"$argv[1]\" = use; and not contains -- --help $argv; and not contains -- -h $argv\n        command nvm use --shell fish $argv[2..-1] | source\n    else if test (count $argv) -gt 0; and test \"$argv[1]\" = deactivate; and not contains -- --help $argv; and not contains -- -h $argv\n        command nvm deactivate --shell fish | source\n    else\n        command nvm $argv\n    end\nend\n".to_string(),
+        ShellType::Bash | ShellType::Zsh => {
+            let name = if matches!(shell, ShellType::Zsh) { "zsh" } else { "bash" };
+            format!(
+                "nvm() {{\n    if [ \"$1\" = use ] && [ \"$2\" != --help ] && [ \"$2\" != -h ]; then\n        shift\n        local nvm_env\n        nvm_env=\"$(command nvm use --shell {name} \"$@\")\" && eval \"$nvm_env\"\n    elif [ \"$1\" = deactivate ] && [ \"$2\" != --help ] && [ \"$2\" != -h ]; then\n        local nvm_env\n        nvm_env=\"$(command nvm deactivate --shell {name})\" && eval \"$nvm_env\"\n    else\n        command nvm \"$@\"\n    fi\n}}\n",
+                name = name
+            )
+        }
+        ShellType::Nushell | ShellType::Xonsh | ShellType::Elvish => String::new(),
+    }
+}
+
+/// Comillas simples para el shell indicado
+fn quote(shell: ShellType, value: &str) -> String {
+    match shell {
+        ShellType::Fish | ShellType::Xonsh => {
+            format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+        }
+        ShellType::Bash | ShellType::Zsh => format!("'{}'", value.replace('\'', "'\\''")),
+        ShellType::Elvish => format!("'{}'", value.replace('\'', "''")),
+        ShellType::Nushell => serde_json::Value::from(value).to_string(),
+    }
+}
+
+/// Agrega el bloque de nvm-rs, o lo reemplaza si ya existe con otro contenido
+/// (ej: se activó el hook de cambio de directorio)
 pub(crate) fn ensure_shell_block(config_path: &Path, block: &str) -> Result<()> {
     let mut contents = if config_path.exists() {
         fs::read_to_string(config_path).unwrap_or_default()
@@ -71,14 +415,20 @@ pub(crate) fn ensure_shell_block(config_path: &Path, block: &str) -> Result<()>
         String::new()
     };
 
-    if contents.contains(NVM_BLOCK_START) && contents.contains(NVM_BLOCK_END) {
-        return Ok(());
-    }
-
-    if !contents.ends_with('\n') && !contents.is_empty() {
-        contents.push('\n');
+    match find_shell_block(&contents) {
+        Some((start, end)) => {
+            if contents[start..end] == *block {
+                return Ok(());
+            }
+            contents.replace_range(start..end, block);
+        }
+        None => {
+            if !contents.ends_with('\n') && !contents.is_empty() {
+                contents.push('\n');
+            }
+            contents.push_str(block);
+        }
     }
-    contents.push_str(block);
 
     if let Some(parent) = config_path.parent() {
         fs::create_dir_all(parent).ok();
@@ -88,12 +438,47 @@ pub(crate) fn ensure_shell_block(config_path: &Path, block: &str) -> Result<()>
     Ok(())
 }
 
+/// Indica si el archivo tiene un bloque de nvm-rs distinto de `block`
+pub(crate) fn shell_block_outdated(config_path: &Path, block: &str) -> bool {
+    let contents = fs::read_to_string(config_path).unwrap_or_default();
+    find_shell_block(&contents).is_some_and(|(start, end)| contents[start..end] != *block)
+}
+
+/// Rango del bloque de nvm-rs (incluye los marcadores y el salto de línea final)
+fn find_shell_block(contents: &str) -> Option<(usize, usize)> {
+    let start = contents.find(NVM_BLOCK_START)?;
+    let end_marker = start + contents[start..].find(NVM_BLOCK_END)?;
+    let mut end = end_marker + NVM_BLOCK_END.len();
+    if contents[end..].starts_with('\n') {
+        end += 1;
+    }
+    Some((start, end))
+}
+
+/// Elimina el bloque de nvm-rs de la configuración de todos los shells; devuelve los archivos modificados
+pub(crate) fn remove_shell_blocks() -> Result<Vec<PathBuf>> {
+    let mut removed = Vec::new();
+    for (config_path, _) in existing_shell_configs() {
+        let contents = fs::read_to_string(&config_path).unwrap_or_default();
+        if find_shell_block(&contents).is_some() {
+            remove_shell_block(&config_path)?;
+            removed.push(config_path);
+        }
+    }
+    Ok(removed)
+}
+
+/// Elimina el bloque de nvm-rs; los archivos sin bloque no se modifican
 pub(crate) fn remove_shell_block(config_path: &Path) -> Result<()> {
     if !config_path.exists() {
         return Ok(());
     }
 
     let contents = fs::read_to_string(config_path).unwrap_or_default();
+    if !contents.lines().any(|line| line.trim() == NVM_BLOCK_START) {
+        return Ok(());
+    }
+
     let mut output = String::new();
     let mut skipping = false;
 
@@ -116,3 +501,184 @@ pub(crate) fn remove_shell_block(config_path: &Path) -> Result<()> {
         .map_err(|e| with_context("Failed to update shell configuration", e))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_env_quotes_values() {
+        let vars = [("NVM_NODE", "/home/o'neil/.nvm/versions/v20.11.0/bin".to_string())];
+        let path = [PathBuf::from("/home/o'neil/.nvm/versions/v20.11.0/bin"), PathBuf::from("/usr/bin")];
+
+        let bash = build_session_env(ShellType::Bash, &vars, &[], &path);
+        assert!(bash.contains("export NVM_NODE='/home/o'\\''neil/.nvm/versions/v20.11.0/bin'\n"));
+        assert!(bash.contains("export PATH='/home/o'\\''neil/.nvm/versions/v20.11.0/bin:/usr/bin'\n"));
+
+        let fish = build_session_env(ShellType::Fish, &vars, &["NVM_SESSION_VERSION"], &path);
+        assert!(fish.contains("set -gx NVM_NODE '/home/o\\'neil/.nvm/versions/v20.11.0/bin'\n"));
+        assert!(fish.contains("set -gx PATH '/home/o\\'neil/.nvm/versions/v20.11.0/bin' '/usr/bin'\n"));
+        assert!(fish.contains("set -e NVM_SESSION_VERSION\n"));
+    }
+
+    #[test]
+    fn test_session_env_other_shells() {
+        let vars = [("NVM_SESSION_VERSION", "v20.11.0".to_string())];
+        let path = [PathBuf::from("/home/o'neil/.nvm/versions/v20.11.0/bin"), PathBuf::from("/usr/bin")];
+
+        let nu = build_session_env(ShellType::Nushell, &vars, &["NVM_NODE"], &path);
+        let record: serde_json::Value = serde_json::from_str(&nu).unwrap();
+        assert_eq!(record["NVM_SESSION_VERSION"], "v20.11.0");
+        assert_eq!(record["NVM_NODE"], "");
+        assert_eq!(record["PATH"][0], "/home/o'neil/.nvm/versions/v20.11.0/bin");
+
+        let xonsh = build_session_env(ShellType::Xonsh, &vars, &["NVM_NODE"], &path);
+        assert!(xonsh.contains("$NVM_SESSION_VERSION = 'v20.11.0'\n"));
+        assert!(xonsh.contains("${...}.pop('NVM_NODE', None)\n"));
+        assert!(xonsh.contains("$PATH = ['/home/o\\'neil/.nvm/versions/v20.11.0/bin', '/usr/bin']\n"));
+
+        let elvish = build_session_env(ShellType::Elvish, &vars, &["NVM_NODE"], &path);
+        assert!(elvish.contains("set-env NVM_SESSION_VERSION 'v20.11.0'\n"));
+        assert!(elvish.contains("unset-env NVM_NODE\n"));
+        assert!(elvish.contains("set paths = ['/home/o''neil/.nvm/versions/v20.11.0/bin' '/usr/bin']\n"));
+    }
+
+    #[test]
+    fn test_shell_type_from_shell_path() {
+        assert_eq!(ShellType::from_path("/usr/bin/nu"), Some(ShellType::Nushell));
+        assert_eq!(ShellType::from_path("/opt/homebrew/bin/xonsh"), Some(ShellType::Xonsh));
+        assert_eq!(ShellType::from_path("/usr/local/bin/elvish"), Some(ShellType::Elvish));
+        assert_eq!(ShellType::from_path("/bin/zsh"), Some(ShellType::Zsh));
+        assert_eq!(ShellType::from_path("/usr/bin/zsh5"), Some(ShellType::Zsh));
+        assert_eq!(ShellType::from_path("/usr/local/bin/fish3"), Some(ShellType::Fish));
+        assert_eq!(ShellType::from_path("/bin/tcsh"), None);
+
+        let home = Path::new("/home/dev");
+        assert_eq!(ShellType::Xonsh.config_path(home), home.join(".xonshrc"));
+        assert!(ShellType::Nushell.config_path(home).ends_with("nushell/config.nu"));
+        assert!(ShellType::Elvish.config_path(home).ends_with("elvish/rc.elv"));
+    }
+
+    #[test]
+    fn test_shell_block_other_shells() {
+        let nvm_dir = Path::new("/home/dev/.nvm");
+        let shims = ShellBlockOptions { cd_hook: CdHook::Use, shims: true, ..Default::default() };
+
+        let nu = build_shell_block(nvm_dir, ShellType::Nushell, ShellBlockOptions::default());
+        assert!(nu.contains("$env.NVM_HOME = \"/home/dev/.nvm\"\n"));
+        assert!(nu.contains("prepend [$env.NVM_BIN $env.NVM_NODE] | uniq)"));
+        let nu = build_shell_block(nvm_dir, ShellType::Nushell, shims);
+        assert!(nu.contains("prepend [$env.NVM_BIN ($env.NVM_HOME | path join \"shims\") $env.NVM_NODE]"));
+        // Sin función `nvm` ni hook de cambio de directorio
+        assert!(!nu.contains("_nvm_rs_cd_hook"));
+
+        let xonsh = build_shell_block(nvm_dir, ShellType::Xonsh, shims);
+        assert!(xonsh.contains(
+            "$PATH.insert(0, $NVM_NODE)\n$PATH.insert(0, $NVM_HOME + \"/shims\")\n$PATH.insert(0, $NVM_BIN)\n"
+        ));
+
+        let elvish = build_shell_block(nvm_dir, ShellType::Elvish, ShellBlockOptions::default());
+        assert!(elvish.contains("set-env NVM_NODE $E:NVM_HOME/current/bin\n"));
+        assert!(elvish.contains("set paths = [$E:NVM_BIN $E:NVM_NODE $@paths]\n"));
+    }
+
+    #[test]
+    fn test_remove_shell_block_leaves_other_files_untouched() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let config_path = temp.path().join("config.nu");
+        let block = build_shell_block(Path::new("/home/dev/.nvm"), ShellType::Nushell, ShellBlockOptions::default());
+        fs::write(&config_path, format!("$env.config.show_banner = false\n{}", block)).unwrap();
+        remove_shell_block(&config_path).unwrap();
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), "$env.config.show_banner = false\n");
+
+        // Sin bloque no se reescribe (ej: no se agrega un salto de línea final)
+        let other = temp.path().join("rc.elv");
+        fs::write(&other, "use str").unwrap();
+        remove_shell_block(&other).unwrap();
+        assert_eq!(fs::read_to_string(&other).unwrap(), "use str");
+    }
+
+    fn with_hook(cd_hook: CdHook) -> ShellBlockOptions {
+        ShellBlockOptions { cd_hook, ..Default::default() }
+    }
+
+    #[test]
+    fn test_shell_block_shims_path() {
+        let nvm_dir = Path::new("/home/dev/.nvm");
+        let options = ShellBlockOptions { shims: true, ..Default::default() };
+        let bash = build_shell_block(nvm_dir, ShellType::Bash, options);
+        assert!(bash.contains("export PATH=\"$NVM_BIN:$NVM_HOME/shims:$NVM_NODE:$PATH\""));
+        let fish = build_shell_block(nvm_dir, ShellType::Fish, options);
+        assert!(fish.contains("fish_add_path $NVM_BIN $NVM_HOME/shims $NVM_NODE"));
+    }
+
+    #[test]
+    fn test_shell_block_completions() {
+        let nvm_dir = Path::new("/home/dev/.nvm");
+        let options = ShellBlockOptions { completions: true, ..Default::default() };
+        let bash = build_shell_block(nvm_dir, ShellType::Bash, options);
+        assert!(bash.ends_with("eval \"$(command nvm completions bash 2>/dev/null)\"\n# <<< nvm-rs <<<\n"));
+        let fish = build_shell_block(nvm_dir, ShellType::Fish, options);
+        assert!(fish.contains("command nvm completions fish 2>/dev/null | source\n"));
+        let plain = build_shell_block(nvm_dir, ShellType::Zsh, ShellBlockOptions::default());
+        assert!(!plain.contains("completions"));
+    }
+
+    #[test]
+    fn test_shell_block_cd_hook() {
+        let nvm_dir = Path::new("/home/dev/.nvm");
+        let plain = build_shell_block(nvm_dir, ShellType::Bash, with_hook(CdHook::Off));
+        assert!(!plain.contains("_nvm_rs_cd_hook"));
+        assert!(!plain.contains("nvm()"));
+
+        let bash = build_shell_block(nvm_dir, ShellType::Bash, with_hook(CdHook::Use));
+        assert!(bash.contains("PROMPT_COMMAND=\"_nvm_rs_cd_hook"));
+        assert!(bash.contains("command nvm use --shell bash \"$version\""));
+        assert!(bash.contains("nvm()"));
+        assert!(!bash.contains("command nvm install"));
+
+        let zsh = build_shell_block(nvm_dir, ShellType::Zsh, with_hook(CdHook::UseAndInstall));
+        assert!(zsh.contains("add-zsh-hook chpwd _nvm_rs_cd_hook"));
+        assert!(zsh.contains("command nvm install \"$version\""));
+
+        let fish = build_shell_block(nvm_dir, ShellType::Fish, with_hook(CdHook::Use));
+        assert!(fish.contains("function _nvm_rs_cd_hook --on-variable PWD"));
+        assert!(fish.ends_with(&format!("{}\n", NVM_BLOCK_END)));
+    }
+
+    #[test]
+    fn test_ensure_shell_block_replaces_outdated_block() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let rc = temp.path().join(".bashrc");
+        let nvm_dir = Path::new("/home/dev/.nvm");
+        fs::write(&rc, "alias ll='ls -l'\n").unwrap();
+
+        let old = build_shell_block(nvm_dir, ShellType::Bash, with_hook(CdHook::Off));
+        ensure_shell_block(&rc, &old).unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&rc)
+            .and_then(|mut f| std::io::Write::write_all(&mut f, b"export EDITOR=vim\n"))
+            .unwrap();
+
+        let new = build_shell_block(nvm_dir, ShellType::Bash, with_hook(CdHook::Use));
+        assert!(shell_block_outdated(&rc, &new));
+        ensure_shell_block(&rc, &new).unwrap();
+        assert!(!shell_block_outdated(&rc, &new));
+
+        let contents = fs::read_to_string(&rc).unwrap();
+        assert!(contents.starts_with("alias ll='ls -l'\n"));
+        assert!(contents.ends_with("export EDITOR=vim\n"));
+        assert_eq!(contents.matches(NVM_BLOCK_START).count(), 1);
+        assert!(contents.contains("_nvm_rs_cd_hook"));
+    }
+
+    #[test]
+    fn test_session_wrapper_only_intercepts_use() {
+        let zsh = build_session_wrapper(ShellType::Zsh);
+        assert!(zsh.contains("command nvm use --shell zsh \"$@\""));
+        assert!(zsh.contains("command nvm deactivate --shell zsh)"));
+        assert!(zsh.contains("command nvm \"$@\""));
+        assert!(build_session_wrapper(ShellType::Fish).contains("| source"));
+    }
+}
@@ -20,12 +20,13 @@ pub fn full_uninstall_cleanup(
 
     // Eliminar configuración del shell si --remove-config
     if remove_config {
-        if let Ok((config_path, _)) = crate::platform::unix::shell::detect_shell_config() {
-            if let Err(e) = crate::platform::unix::shell::remove_shell_block(&config_path) {
-                eprintln!("⚠ Could not remove shell configuration: {}", e);
-            } else {
-                println!("✓ Shell configuration removed from {}", config_path.display());
+        match crate::platform::unix::shell::remove_shell_blocks() {
+            Ok(removed) => {
+                for config_path in removed {
+                    println!("✓ Shell configuration removed from {}", config_path.display());
+                }
             }
+            Err(e) => eprintln!("⚠ Could not remove shell configuration: {}", e),
         }
     }
 
@@ -1,28 +1,87 @@
+use crate::config::{Config, HttpConfig};
 use crate::error::{message, with_context, Result};
 use indicatif::{ProgressBar, ProgressStyle};
-use reqwest::Client;
+use reqwest::{Certificate, Client, NoProxy, Proxy, RequestBuilder};
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 use std::time::Duration;
 
-/// Creates a configured HTTP client with appropriate headers and timeout
-#[allow(dead_code)] // Will be used in Phase 3 (download)
-pub fn create_client() -> Result<Client> {
-    let client = Client::builder()
-        .timeout(Duration::from_secs(30))
-        .user_agent("nvm-rs/0.1.0")
+/// User agent reported on every request
+pub const USER_AGENT: &str = concat!("nvm-rs/", env!("CARGO_PKG_VERSION"));
+
+/// Creates the HTTP client used for every network call.
+/// Honors proxy settings, the extra CA bundle and the configured timeouts.
+pub fn create_client(config: &Config) -> Result<Client> {
+    let http = &config.http;
+    let mut builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(Duration::from_secs(http.timeout_secs))
+        .connect_timeout(Duration::from_secs(http.connect_timeout_secs));
+
+    if let Some(proxy) = build_proxy(http)? {
+        builder = builder.proxy(proxy);
+    }
+
+    for cert in load_ca_certificates(http)? {
+        builder = builder.add_root_certificate(cert);
+    }
+
+    let client = builder
         .build()
         .map_err(|e| with_context("Failed to create HTTP client", e))?;
-    
+
     Ok(client)
 }
 
+/// Builds a GET request carrying the credentials configured for the URL's host
+pub fn authorized_get(client: &Client, url: &str, config: &Config) -> RequestBuilder {
+    crate::utils::auth::authorize(client.get(url), url, config)
+}
+
+/// Builds the explicit proxy, if any. Without one, reqwest falls back to the
+/// system proxy settings.
+fn build_proxy(http: &HttpConfig) -> Result<Option<Proxy>> {
+    let Some(proxy_url) = http.proxy.as_deref() else {
+        return Ok(None);
+    };
+
+    let proxy = Proxy::all(proxy_url)
+        .map_err(|e| with_context(&format!("Invalid proxy URL: {}", proxy_url), e))?
+        .no_proxy(http.no_proxy.as_deref().and_then(NoProxy::from_string));
+
+    Ok(Some(proxy))
+}
+
+/// Loads the extra root certificates from the configured PEM bundle
+fn load_ca_certificates(http: &HttpConfig) -> Result<Vec<Certificate>> {
+    let Some(path) = http.ca_bundle.as_deref() else {
+        return Ok(vec![]);
+    };
+
+    let pem = std::fs::read(path).map_err(|e| {
+        with_context(&format!("Failed to read CA bundle: {}", path.display()), e)
+    })?;
+
+    let certs = Certificate::from_pem_bundle(&pem).map_err(|e| {
+        with_context(&format!("Invalid CA bundle: {}", path.display()), e)
+    })?;
+
+    if certs.is_empty() {
+        return Err(message(format!(
+            "No certificates found in CA bundle: {}",
+            path.display()
+        )));
+    }
+
+    Ok(certs)
+}
+
 /// Downloads a file from the given URL and saves it to the destination path
 /// Shows a progress bar during download
 #[allow(dead_code)] // Will be used in Phase 3 (install)
-pub async fn download_file(url: &str, dest: &Path) -> Result<()> {
-    let client = create_client()?;
+pub async fn download_file(url: &str, dest: &Path, config: &Config) -> Result<()> {
+    let client = create_client(config)?;
     
     // Make initial request to get content length
     let response = client
@@ -63,8 +122,8 @@ pub async fn download_file(url: &str, dest: &Path) -> Result<()> {
 
 /// Downloads JSON data from a URL with retry logic
 #[allow(dead_code)] // Will be used in Phase 3-4 (install, version checking)
-pub async fn download_json(url: &str, max_retries: u32) -> Result<String> {
-    let client = create_client()?;
+pub async fn download_json(url: &str, max_retries: u32, config: &Config) -> Result<String> {
+    let client = create_client(config)?;
     let mut last_error = None;
     
     for attempt in 0..=max_retries {
@@ -102,8 +161,8 @@ pub async fn download_json(url: &str, max_retries: u32) -> Result<String> {
 
 /// Checks if a URL is accessible (HEAD request)
 #[allow(dead_code)] // Used in tests and will be used in Phase 5 (verification)
-pub async fn check_url(url: &str) -> bool {
-    let client = match create_client() {
+pub async fn check_url(url: &str, config: &Config) -> bool {
+    let client = match create_client(config) {
         Ok(c) => c,
         Err(_) => return false,
     };
@@ -120,22 +179,48 @@ mod tests {
 
     #[tokio::test]
     async fn test_check_url_success() {
-        let result = check_url("https://nodejs.org/dist/index.json").await;
+        let config = Config::new().unwrap();
+        let result = check_url("https://nodejs.org/dist/index.json", &config).await;
         assert!(result, "nodejs.org should be accessible");
     }
 
     #[tokio::test]
     async fn test_check_url_failure() {
-        let result = check_url("https://invalid-domain-that-does-not-exist-12345.com").await;
+        let config = Config::new().unwrap();
+        let result = check_url("https://invalid-domain-that-does-not-exist-12345.com", &config).await;
         assert!(!result, "Invalid domain should not be accessible");
     }
 
     #[tokio::test]
     async fn test_download_json() {
-        let result = download_json("https://nodejs.org/dist/index.json", 2).await;
+        let config = Config::new().unwrap();
+        let result = download_json("https://nodejs.org/dist/index.json", 2, &config).await;
         assert!(result.is_ok(), "Should download Node.js version index");
         
         let json = result.unwrap();
         assert!(json.contains("version"), "Response should contain version data");
     }
+
+    #[test]
+    fn test_user_agent_reports_crate_version() {
+        assert_eq!(USER_AGENT, format!("nvm-rs/{}", env!("CARGO_PKG_VERSION")));
+    }
+
+    #[test]
+    fn test_missing_ca_bundle_is_an_error() {
+        let http = HttpConfig {
+            ca_bundle: Some(std::path::PathBuf::from("/nonexistent/nvm-ca.pem")),
+            ..HttpConfig::default()
+        };
+        assert!(load_ca_certificates(&http).is_err());
+    }
+
+    #[test]
+    fn test_invalid_proxy_is_an_error() {
+        let http = HttpConfig {
+            proxy: Some("not a url".to_string()),
+            ..HttpConfig::default()
+        };
+        assert!(build_proxy(&http).is_err());
+    }
 }